@@ -0,0 +1,149 @@
+use super::{AssetCooker,CookError};
+use crate::core::algebra::{Vector2,Vector3};
+use crate::core::math::TriangleDefinition;
+use crate::render::mesh_format::{write_cooked_mesh};
+use crate::systems::surface::buffer::{TriangleBuffer,VertexBuffer};
+use crate::systems::surface::surface::SurfaceData;
+use crate::systems::surface::vertex::StaticVertex;
+use std::path::Path;
+
+/// Cooks OBJ and glTF files into `.mesh` files. Every mesh of the file is merged into one
+/// `SurfaceData` of `StaticVertex`es; missing normals are generated, tangents always are.
+/// A `.gltf` file may reference `.bin` buffers next to it.
+#[derive(Default)]
+pub struct MeshCooker{}
+
+impl AssetCooker for MeshCooker{
+    fn version(&self)->u32{
+        1
+    }
+
+    fn source_extensions(&self)->&[&str]{
+        &["obj","gltf","glb"]
+    }
+
+    fn output_extension(&self)->&str{
+        "mesh"
+    }
+
+    fn cook(&self,source: &[u8],_meta: Option<&[u8]>,source_path: &Path)->Result<Vec<u8>,CookError>{
+        let decode_error = |message: String|CookError::Decode{path:source_path.to_path_buf(),message};
+        let is_obj = source_path.extension().map_or(false,|ext|ext.eq_ignore_ascii_case("obj"));
+        let geometry = if is_obj{read_obj(source)}else{read_gltf(source,source_path)}.map_err(decode_error)?;
+        let data = geometry.into_surface().map_err(decode_error)?;
+
+        let mut cooked = Vec::new();
+        write_cooked_mesh(&data,&mut cooked).map_err(|error|CookError::Io{path:source_path.to_path_buf(),error})?;
+        return Ok(cooked);
+    }
+}
+
+#[derive(Default)]
+struct Geometry{
+    vertices: Vec<StaticVertex>,
+    triangles: Vec<TriangleDefinition>,
+    missing_normals: bool,
+}
+
+impl Geometry{
+    fn into_surface(self)->Result<SurfaceData,String>{
+        if self.vertices.is_empty(){
+            return Err("no triangles in source".to_string());
+        }
+        let vertex_buffer = VertexBuffer::new(self.vertices.len(),StaticVertex::layout(),self.vertices)
+            .map_err(|e|e.to_string())?;
+        let mut data = SurfaceData::new(vertex_buffer,TriangleBuffer::new(self.triangles));
+        if self.missing_normals{
+            data.calculate_normals().map_err(|e|e.to_string())?;
+        }
+        data.calculate_tangents().map_err(|e|e.to_string())?;
+        return Ok(data);
+    }
+
+    fn push_triangles(&mut self,base: u32,indices: &[u32],vertex_count: usize)->Result<(),String>{
+        if indices.iter().any(|&index|index as usize >= vertex_count){
+            return Err("vertex index out of bounds".to_string());
+        }
+        self.triangles.extend(indices.chunks_exact(3).map(|i|TriangleDefinition([base + i[0],base + i[1],base + i[2]])));
+        return Ok(());
+    }
+}
+
+fn read_obj(source: &[u8])->Result<Geometry,String>{
+    let options = tobj::LoadOptions{
+        triangulate: true,
+        single_index: true,
+        ..Default::default()
+    };
+    // materials are not cooked, so referenced .mtl files are not opened
+    let (models,_) = tobj::load_obj_buf(&mut std::io::Cursor::new(source),&options,|_|Err(tobj::LoadError::OpenFileFailed))
+        .map_err(|e|e.to_string())?;
+
+    let mut geometry = Geometry::default();
+    for model in models{
+        let mesh = model.mesh;
+        let vertex_count = mesh.positions.len() / 3;
+        let has_normals = mesh.normals.len() == mesh.positions.len();
+        let has_tex_coords = mesh.texcoords.len() / 2 == vertex_count;
+        geometry.missing_normals |= !has_normals;
+        let base = geometry.vertices.len() as u32;
+        for i in 0..vertex_count{
+            let position = Vector3::new(mesh.positions[i * 3],mesh.positions[i * 3 + 1],mesh.positions[i * 3 + 2]);
+            let tex_coord = if has_tex_coords{
+                // OBJ puts the texture origin at the bottom left
+                Vector2::new(mesh.texcoords[i * 2],1.0 - mesh.texcoords[i * 2 + 1])
+            }else{
+                Vector2::default()
+            };
+            let normal = if has_normals{
+                Vector3::new(mesh.normals[i * 3],mesh.normals[i * 3 + 1],mesh.normals[i * 3 + 2])
+            }else{
+                Vector3::default()
+            };
+            geometry.vertices.push(StaticVertex::from_pos_uv_normal(position,tex_coord,normal));
+        }
+        geometry.push_triangles(base,&mesh.indices,vertex_count)?;
+    }
+    return Ok(geometry);
+}
+
+fn read_gltf(source: &[u8],source_path: &Path)->Result<Geometry,String>{
+    let gltf = gltf::Gltf::from_slice(source).map_err(|e|e.to_string())?;
+    let buffers = gltf::import_buffers(&gltf.document,source_path.parent(),gltf.blob.clone())
+        .map_err(|e|e.to_string())?;
+
+    let mut geometry = Geometry::default();
+    for mesh in gltf.document.meshes(){
+        for primitive in mesh.primitives(){
+            if primitive.mode() != gltf::mesh::Mode::Triangles{
+                continue;
+            }
+            let reader = primitive.reader(|buffer|buffers.get(buffer.index()).map(|data|&data.0[..]));
+            let positions: Vec<[f32;3]> = match reader.read_positions(){
+                Some(positions)=>positions.collect(),
+                None=>continue,
+            };
+            let normals: Option<Vec<[f32;3]>> = reader.read_normals().map(|normals|normals.collect());
+            let tex_coords: Option<Vec<[f32;2]>> = reader.read_tex_coords(0).map(|tex_coords|tex_coords.into_f32().collect());
+            let indices: Vec<u32> = match reader.read_indices(){
+                Some(indices)=>indices.into_u32().collect(),
+                None=>(0..positions.len() as u32).collect(),
+            };
+            geometry.missing_normals |= normals.is_none();
+
+            let base = geometry.vertices.len() as u32;
+            for (i,position) in positions.iter().enumerate(){
+                let tex_coord = tex_coords.as_ref()
+                    .and_then(|tex_coords|tex_coords.get(i))
+                    .map_or(Vector2::default(),|uv|Vector2::new(uv[0],uv[1]));
+                let normal = normals.as_ref()
+                    .and_then(|normals|normals.get(i))
+                    .map_or(Vector3::default(),|n|Vector3::new(n[0],n[1],n[2]));
+                let position = Vector3::new(position[0],position[1],position[2]);
+                geometry.vertices.push(StaticVertex::from_pos_uv_normal(position,tex_coord,normal));
+            }
+            geometry.push_triangles(base,&indices,positions.len())?;
+        }
+    }
+    return Ok(geometry);
+}
@@ -0,0 +1,45 @@
+//! Offline asset processing. `AssetProcessor` walks a source directory and turns source files
+//! (PNG/JPEG images, OBJ/glTF meshes) into the cooked formats the runtime loaders read, see
+//! `render::texture_format` and `render::mesh_format`. Every other file is copied as is.
+//!
+//! Processing is incremental: the `CookManifest` written next to the output remembers the
+//! content hash of each source and its `.meta` file, and of each copied file. Unchanged files
+//! are skipped, the outputs of files that disappeared are deleted. The same manifest lets
+//! `AssetServer::load_cook_manifest` map source paths to cooked ones.
+
+mod mesh_cooker;
+mod processor;
+mod texture_cooker;
+
+pub use mesh_cooker::{MeshCooker};
+pub use processor::{AssetProcessor,ProcessReport};
+pub use texture_cooker::{TextureCooker};
+
+use std::path::{Path,PathBuf};
+
+/// Turns one kind of source file into a cooked engine file.
+pub trait AssetCooker: Send + Sync + 'static{
+    /// Bump when the output for the same input changes, so existing outputs are cooked again.
+    fn version(&self)->u32;
+
+    /// Lowercase source extensions, without the dot.
+    fn source_extensions(&self)->&[&str];
+
+    /// Extension of the cooked file, it replaces the source extension.
+    fn output_extension(&self)->&str;
+
+    /// `meta` holds the source's `.meta` file, if any. `source_path` is the path on disk,
+    /// for formats that reference neighbouring files.
+    fn cook(&self,source: &[u8],meta: Option<&[u8]>,source_path: &Path)->Result<Vec<u8>,CookError>;
+}
+
+/// Set of possible cooking errors.
+#[derive(Debug, thiserror::Error)]
+pub enum CookError{
+    #[error("Failed to access {path:?}: {error}")]
+    Io{ path: PathBuf, error: std::io::Error },
+    #[error("Failed to decode {path:?}: {message}")]
+    Decode{ path: PathBuf, message: String },
+    #[error("Invalid meta file for {path:?}: {message}")]
+    InvalidMeta{ path: PathBuf, message: String },
+}
@@ -0,0 +1,251 @@
+use super::{AssetCooker,CookError,MeshCooker,TextureCooker};
+use crate::asset_server::cook_manifest::{CookManifest,CookedAsset,COOK_MANIFEST_PATH};
+use crate::log::{info};
+use crate::utils::fnv1a_hash;
+use std::collections::{BTreeMap,BTreeSet};
+use std::path::{Path,PathBuf};
+
+/// Cooks a source directory into an output directory, see the module docs.
+pub struct AssetProcessor{
+    source_root: PathBuf,
+    output_root: PathBuf,
+    cookers: Vec<Box<dyn AssetCooker>>,
+}
+
+/// What one `AssetProcessor::process` run did, paths are relative to the source root.
+#[derive(Debug,Default)]
+pub struct ProcessReport{
+    pub cooked: Vec<String>,
+    /// Sources and plain files whose hashes match the manifest.
+    pub skipped: Vec<String>,
+    /// Files without a cooker, copied to the output because they changed.
+    pub copied: Vec<String>,
+    /// Sources and plain files that disappeared, their outputs were deleted.
+    pub removed: Vec<String>,
+    pub failed: Vec<(String,CookError)>,
+}
+
+impl AssetProcessor{
+    /// Creates a processor with `TextureCooker` and `MeshCooker`.
+    pub fn new<S: Into<PathBuf>,O: Into<PathBuf>>(source_root: S,output_root: O)->Self{
+        let mut processor = Self{
+            source_root: source_root.into(),
+            output_root: output_root.into(),
+            cookers: Vec::new(),
+        };
+        processor.add_cooker(TextureCooker::default());
+        processor.add_cooker(MeshCooker::default());
+        return processor;
+    }
+
+    /// A later cooker wins over earlier ones for the same source extension.
+    pub fn add_cooker<C: AssetCooker>(&mut self,cooker: C)->&mut Self{
+        self.cookers.insert(0,Box::new(cooker));
+        return self;
+    }
+
+    /// Cooks changed sources, copies changed plain files and rewrites the manifest. A source
+    /// that fails to cook is reported and keeps its previous output, if any.
+    pub fn process(&self)->Result<ProcessReport,CookError>{
+        let manifest_path = self.output_root.join(COOK_MANIFEST_PATH);
+        // an unreadable manifest only costs a full cook
+        let old_manifest = std::fs::read(&manifest_path).ok()
+            .and_then(|bytes|CookManifest::from_bytes(&bytes).ok())
+            .unwrap_or_default();
+
+        let sources = self.collect_sources()?;
+        let mut report = ProcessReport::default();
+        let mut entries = BTreeMap::new();
+        let mut copied = BTreeMap::new();
+        for source in sources.iter(){
+            if is_meta(source) && self.find_cooker(&source[..source.len() - ".meta".len()]).is_some(){
+                // baked into the cooked file
+                continue;
+            }
+            let source_path = self.source_root.join(source);
+            let bytes = std::fs::read(&source_path).map_err(|error|CookError::Io{path:source_path.clone(),error})?;
+            let cooker = match self.find_cooker(source){
+                Some(cooker)=>cooker,
+                None=>{
+                    let hash = fnv1a_hash(&bytes);
+                    if self.copy_if_changed(source,&bytes,old_manifest.copied.get(source) == Some(&hash))?{
+                        report.copied.push(source.clone());
+                    }else{
+                        report.skipped.push(source.clone());
+                    }
+                    copied.insert(source.clone(),hash);
+                    continue;
+                },
+            };
+
+            let meta_path = self.source_root.join(format!("{}.meta",source));
+            let meta = std::fs::read(&meta_path).ok();
+            let entry = CookedAsset{
+                output: Path::new(source).with_extension(cooker.output_extension()).to_string_lossy().replace('\\',"/"),
+                source_hash: fnv1a_hash(&bytes),
+                meta_hash: meta.as_deref().map(fnv1a_hash),
+                cooker_version: cooker.version(),
+            };
+            let output_path = self.output_root.join(&entry.output);
+            if old_manifest.entries.get(source) == Some(&entry) && output_path.exists(){
+                report.skipped.push(source.clone());
+                entries.insert(source.clone(),entry);
+                continue;
+            }
+            match cooker.cook(&bytes,meta.as_deref(),&source_path){
+                Ok(cooked)=>{
+                    write_file(&output_path,&cooked)?;
+                    info!("cooked {} -> {}",source,entry.output);
+                    report.cooked.push(source.clone());
+                    entries.insert(source.clone(),entry);
+                },
+                Err(error)=>{
+                    if let Some(old_entry) = old_manifest.entries.get(source){
+                        entries.insert(source.clone(),old_entry.clone());
+                    }
+                    report.failed.push((source.clone(),error));
+                },
+            }
+        }
+
+        // an output may share its path with a file that replaced the source it came from
+        let outputs: BTreeSet<&str> = entries.values().map(|entry|entry.output.as_str())
+            .chain(copied.keys().map(|path|path.as_str()))
+            .collect();
+        let orphans = old_manifest.entries.iter().map(|(source,entry)|(source,entry.output.as_str()))
+            .chain(old_manifest.copied.keys().map(|path|(path,path.as_str())))
+            .filter(|(source,_)|!sources.contains(*source));
+        for (source,output) in orphans{
+            let output_path = self.output_root.join(output);
+            if !outputs.contains(output) && output_path.exists(){
+                std::fs::remove_file(&output_path).map_err(|error|CookError::Io{path:output_path.clone(),error})?;
+            }
+            report.removed.push(source.clone());
+        }
+
+        let manifest = CookManifest{entries,copied};
+        write_file(&manifest_path,manifest.to_ron_string().as_bytes())?;
+        return Ok(report);
+    }
+
+    fn find_cooker(&self,source: &str)->Option<&dyn AssetCooker>{
+        let extension = Path::new(source).extension()?.to_str()?.to_lowercase();
+        return self.cookers.iter()
+            .find(|cooker|cooker.source_extensions().contains(&extension.as_str()))
+            .map(|cooker|&**cooker);
+    }
+
+    /// Relative paths of every file below the source root, with `/` separators.
+    fn collect_sources(&self)->Result<BTreeSet<String>,CookError>{
+        let io_error = |path: &Path,error|CookError::Io{path:path.to_path_buf(),error};
+        let mut sources = BTreeSet::new();
+        let mut pending_dirs = vec![self.source_root.clone()];
+        while let Some(dir) = pending_dirs.pop(){
+            for entry in std::fs::read_dir(&dir).map_err(|e|io_error(&dir,e))?{
+                let path = entry.map_err(|e|io_error(&dir,e))?.path();
+                // the output may live inside the source tree
+                if path == self.output_root{
+                    continue;
+                }
+                if path.is_dir(){
+                    pending_dirs.push(path);
+                    continue;
+                }
+                let relative_path = path.strip_prefix(&self.source_root)
+                    .expect("walked paths are below the root")
+                    .to_string_lossy()
+                    .replace('\\',"/");
+                if relative_path != COOK_MANIFEST_PATH{
+                    sources.insert(relative_path);
+                }
+            }
+        }
+        return Ok(sources);
+    }
+
+    /// Copies a plain file unless the manifest has its hash and its copy is still there.
+    /// Returns whether it was written.
+    fn copy_if_changed(&self,source: &str,bytes: &[u8],in_manifest: bool)->Result<bool,CookError>{
+        let output_path = self.output_root.join(source);
+        if output_path == self.source_root.join(source) || (in_manifest && output_path.exists()){
+            return Ok(false);
+        }
+        write_file(&output_path,bytes)?;
+        return Ok(true);
+    }
+}
+
+fn is_meta(path: &str)->bool{
+    path.ends_with(".meta")
+}
+
+fn write_file(path: &Path,bytes: &[u8])->Result<(),CookError>{
+    let io_error = |error|CookError::Io{path:path.to_path_buf(),error};
+    if let Some(parent) = path.parent(){
+        std::fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    return std::fs::write(path,bytes).map_err(io_error);
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Cooks `.up` files into upper case `.cooked` ones.
+    struct UpperCaseCooker;
+
+    impl AssetCooker for UpperCaseCooker{
+        fn version(&self)->u32{
+            1
+        }
+
+        fn source_extensions(&self)->&[&str]{
+            &["up"]
+        }
+
+        fn output_extension(&self)->&str{
+            "cooked"
+        }
+
+        fn cook(&self,source: &[u8],_meta: Option<&[u8]>,_source_path: &Path)->Result<Vec<u8>,CookError>{
+            return Ok(source.to_ascii_uppercase());
+        }
+    }
+
+    #[test]
+    fn second_cook_only_processes_changed_files(){
+        let root = std::env::temp_dir().join(format!("pf_cook_test_{}",std::process::id()));
+        let (source_root,output_root) = (root.join("source"),root.join("output"));
+        std::fs::create_dir_all(&source_root).unwrap();
+        std::fs::write(source_root.join("a.up"),"a").unwrap();
+        std::fs::write(source_root.join("b.up"),"b").unwrap();
+        std::fs::write(source_root.join("notes.txt"),"n").unwrap();
+        let mut processor = AssetProcessor::new(&source_root,&output_root);
+        processor.add_cooker(UpperCaseCooker);
+
+        let report = processor.process().unwrap();
+        assert_eq!(report.cooked,vec!["a.up","b.up"]);
+        assert_eq!(report.copied,vec!["notes.txt"]);
+
+        let report = processor.process().unwrap();
+        assert!(report.cooked.is_empty() && report.copied.is_empty());
+        assert_eq!(report.skipped,vec!["a.up","b.up","notes.txt"]);
+
+        std::fs::write(source_root.join("a.up"),"aa").unwrap();
+        std::fs::write(source_root.join("notes.txt"),"nn").unwrap();
+        let report = processor.process().unwrap();
+        assert_eq!(report.cooked,vec!["a.up"]);
+        assert_eq!(report.copied,vec!["notes.txt"]);
+        assert_eq!(report.skipped,vec!["b.up"]);
+        assert_eq!(std::fs::read(output_root.join("a.cooked")).unwrap(),b"AA");
+        assert_eq!(std::fs::read(output_root.join("notes.txt")).unwrap(),b"nn");
+
+        std::fs::remove_file(source_root.join("notes.txt")).unwrap();
+        std::fs::remove_file(source_root.join("b.up")).unwrap();
+        let report = processor.process().unwrap();
+        assert_eq!(report.removed,vec!["b.up","notes.txt"]);
+        assert!(!output_root.join("notes.txt").exists());
+        assert!(!output_root.join("b.cooked").exists());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,41 @@
+use super::{AssetCooker,CookError};
+use crate::render::texture_format::{write_cooked_texture};
+use crate::render::texture_import::{decode_image};
+use crate::render::texture_loader::{TextureImportSettings};
+use std::path::Path;
+
+/// Cooks PNG and JPEG images into `.texture` files, with the `TextureImportSettings` of the
+/// image's `.meta` file baked in.
+#[derive(Default)]
+pub struct TextureCooker{}
+
+impl AssetCooker for TextureCooker{
+    fn version(&self)->u32{
+        1
+    }
+
+    fn source_extensions(&self)->&[&str]{
+        &["png","jpg","jpeg"]
+    }
+
+    fn output_extension(&self)->&str{
+        "texture"
+    }
+
+    fn cook(&self,source: &[u8],meta: Option<&[u8]>,source_path: &Path)->Result<Vec<u8>,CookError>{
+        let settings: TextureImportSettings = match meta{
+            Some(meta)=>ron::de::from_bytes(meta).map_err(|e|CookError::InvalidMeta{
+                path: source_path.to_path_buf(),
+                message: e.to_string(),
+            })?,
+            None=>Default::default(),
+        };
+        let decode_error = |message|CookError::Decode{path:source_path.to_path_buf(),message};
+        let mut data = decode_image(source,source_path.to_path_buf()).map_err(decode_error)?;
+        settings.apply(&mut data).map_err(decode_error)?;
+
+        let mut cooked = Vec::new();
+        write_cooked_texture(&data,&mut cooked).map_err(|error|CookError::Io{path:source_path.to_path_buf(),error})?;
+        return Ok(cooked);
+    }
+}
@@ -0,0 +1,27 @@
+use super::handle::{Handle,HandleId};
+use super::error::{AssetLoadError};
+use bevy::asset::{Asset};
+
+/// Sent once for every load that did not produce its asset, the handle stays in `LoadState::Failed`.
+#[derive(Debug)]
+pub struct AssetLoadFailed {
+    pub id: HandleId,
+    pub error: AssetLoadError,
+}
+
+/// A change of `Assets<T>`, sent during `AssetStage::AssetEvents`. The handles are weak.
+pub enum AssetEvent<T: Asset> {
+    Created { handle: Handle<T> },
+    Modified { handle: Handle<T> },
+    Removed { handle: Handle<T> },
+    /// Removed to stay within `Assets::set_memory_budget`.
+    Evicted { handle: Handle<T> },
+}
+
+impl<T: Asset> AssetEvent<T> {
+    pub fn handle(&self)->&Handle<T>{
+        match self{
+            AssetEvent::Created{handle} | AssetEvent::Modified{handle} | AssetEvent::Removed{handle} | AssetEvent::Evicted{handle}=>handle,
+        }
+    }
+}
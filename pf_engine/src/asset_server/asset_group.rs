@@ -0,0 +1,187 @@
+use super::asset_path::{AssetPath};
+use super::asset_server::{AssetServer};
+use super::asset_memory::{AssetMemoryUsage};
+use super::assets::{Assets};
+use super::error::{AssetLoadError};
+use super::handle::{Handle,HandleId,HandleUntyped};
+use super::load_context::{LoadContext};
+use super::load_state::{LoadState};
+use super::loader::{AssetLoader};
+use bevy::prelude::{EventWriter,Res,ResMut};
+use bevy::reflect::TypeUuid;
+use serde::{Deserialize,Serialize};
+use std::collections::{HashMap};
+
+/// A file listing the paths of a group, RON like `(paths: ["level1.mesh", "rock.texture"])`.
+/// Its extension is `.group.ron`.
+#[derive(Clone,Debug,Default,Deserialize,Serialize,TypeUuid)]
+#[uuid = "5f0c1e7a-8d42-4b6e-b3a9-71c2d4e8f016"]
+pub struct AssetGroupManifest{
+    pub paths: Vec<String>,
+}
+
+impl AssetMemoryUsage for AssetGroupManifest{}
+
+#[derive(Default)]
+pub struct AssetGroupManifestLoader{}
+
+impl AssetLoader for AssetGroupManifestLoader{
+    type Asset = AssetGroupManifest;
+    type Settings = ();
+
+    fn extensions(&self)->&[&str]{
+        return &["group.ron"];
+    }
+    fn load(&self,bytes: &[u8],_settings: &(),load_context: &mut LoadContext)->Result<AssetGroupManifest,AssetLoadError>{
+        return ron::de::from_bytes(bytes)
+            .map_err(|e|AssetLoadError::Decode{path:load_context.path().clone(),message:e.to_string()});
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AssetGroupId(u64);
+
+/// Sent once per group, when every asset of it and their dependencies are loaded or failed.
+#[derive(Debug)]
+pub struct AssetGroupReady{
+    pub id: AssetGroupId,
+    /// Assets of the group that failed, the group is ready all the same.
+    pub failed: Vec<HandleId>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct AssetGroupProgress{
+    pub total: usize,
+    pub loaded: usize,
+    pub failed: Vec<HandleId>,
+}
+
+impl AssetGroupProgress{
+    /// Share of the group that is loaded or failed, 1 for an empty group.
+    pub fn fraction(&self)->f32{
+        if self.total == 0{
+            return 1.0;
+        }
+        return (self.loaded + self.failed.len()) as f32 / self.total as f32;
+    }
+
+    pub fn is_done(&self)->bool{
+        self.loaded + self.failed.len() == self.total
+    }
+}
+
+struct AssetGroup{
+    handles: Vec<HandleUntyped>,
+    /// The manifest whose paths are added once it is loaded.
+    manifest: Option<Handle<AssetGroupManifest>>,
+    ready_sent: bool,
+}
+
+/// Loads sets of assets together, e.g. everything a loading screen waits for. The assets of
+/// a group stay loaded until the group is removed.
+#[derive(Default)]
+pub struct AssetGroups{
+    groups: HashMap<AssetGroupId,AssetGroup>,
+    next_id: u64,
+}
+
+impl AssetGroups{
+    pub fn add<P: Into<AssetPath>,I: IntoIterator<Item=P>>(&mut self,asset_server: &mut AssetServer,paths: I)->AssetGroupId{
+        let handles = paths.into_iter().map(|path|asset_server.load_untyped(path)).collect();
+        return self.insert(AssetGroup{
+            handles,
+            manifest: None,
+            ready_sent: false,
+        });
+    }
+
+    /// The group counts the manifest as one asset until it is loaded, then the assets it lists.
+    pub fn add_manifest<P: Into<AssetPath>>(&mut self,asset_server: &mut AssetServer,manifest_path: P)->AssetGroupId{
+        let manifest = asset_server.load::<AssetGroupManifest,_>(manifest_path);
+        return self.insert(AssetGroup{
+            handles: vec![manifest.clone_untyped()],
+            manifest: Some(manifest),
+            ready_sent: false,
+        });
+    }
+
+    fn insert(&mut self,group: AssetGroup)->AssetGroupId{
+        let id = AssetGroupId(self.next_id);
+        self.next_id += 1;
+        self.groups.insert(id,group);
+        return id;
+    }
+
+    /// Drops the handles of the group.
+    pub fn remove(&mut self,id: AssetGroupId){
+        self.groups.remove(&id);
+    }
+
+    pub fn contains(&self,id: AssetGroupId)->bool{
+        self.groups.contains_key(&id)
+    }
+
+    /// Strong handles of the assets in the group.
+    pub fn handles(&self,id: AssetGroupId)->&[HandleUntyped]{
+        self.groups.get(&id).map_or(&[][..],|group|group.handles.as_slice())
+    }
+
+    /// Counts an asset as loaded only once its dependencies are, see
+    /// `AssetServer::get_dependency_load_state`.
+    pub fn progress(&self,id: AssetGroupId,asset_server: &AssetServer)->Option<AssetGroupProgress>{
+        return self.groups.get(&id).map(|group|group.progress(asset_server));
+    }
+}
+
+impl AssetGroup{
+    fn progress(&self,asset_server: &AssetServer)->AssetGroupProgress{
+        let mut progress = AssetGroupProgress{
+            total: self.handles.len(),
+            ..Default::default()
+        };
+        for handle in self.handles.iter(){
+            match asset_server.get_dependency_load_state(handle.id()){
+                LoadState::Loaded=>progress.loaded += 1,
+                LoadState::Failed=>progress.failed.push(handle.id()),
+                LoadState::Loading | LoadState::NotLoaded=>{},
+            }
+        }
+        return progress;
+    }
+}
+
+pub(crate) fn asset_group_system(
+    mut asset_groups: ResMut<AssetGroups>,
+    mut asset_server: ResMut<AssetServer>,
+    manifests: Res<Assets<AssetGroupManifest>>,
+    mut ready_events: EventWriter<AssetGroupReady>,
+    ){
+    let asset_groups = &mut *asset_groups;
+    for group in asset_groups.groups.values_mut(){
+        let manifest = match group.manifest.as_ref(){
+            Some(manifest)=>manifest,
+            None=>continue,
+        };
+        let paths = match asset_server.get_load_state(manifest){
+            LoadState::Loaded=>manifests.get_asset(manifest).map(|manifest|manifest.paths.clone()).unwrap_or_default(),
+            // reported as a failed asset of the group
+            LoadState::Failed=>Vec::new(),
+            LoadState::Loading | LoadState::NotLoaded=>continue,
+        };
+        // the manifest itself stays in the group
+        group.manifest = None;
+        for path in paths.iter(){
+            group.handles.push(asset_server.load_untyped(path.as_str()));
+        }
+    }
+    for (&id,group) in asset_groups.groups.iter_mut(){
+        if group.ready_sent || group.manifest.is_some(){
+            continue;
+        }
+        let progress = group.progress(&asset_server);
+        if progress.is_done(){
+            group.ready_sent = true;
+            ready_events.send(AssetGroupReady{id,failed:progress.failed});
+        }
+    }
+}
@@ -1,29 +1,34 @@
 use super::handle::{HandleId};
-use std::sync::mpsc::{SyncSender,Receiver,sync_channel};
-use std::sync::Mutex;
+use crossbeam_channel::{Sender,Receiver,unbounded};
+use bevy::asset::{AssetDynamic};
 
 pub(crate) struct AssetLifecycle {
-    pub(crate)sender: SyncSender<AssetLifecycleEvent>,
-    pub(crate)receiver: Mutex<Receiver<AssetLifecycleEvent>>,
+    pub(crate)sender: Sender<AssetLifecycleEvent>,
+    pub(crate)receiver: Receiver<AssetLifecycleEvent>,
+    /// Name of the asset type, for errors about loaders producing another type.
+    pub(crate)type_name: &'static str,
 }
 
 
 pub enum AssetLifecycleEvent {
+    /// A loader finished, the boxed value is the asset type the lifecycle belongs to.
+    Create(HandleId,Box<dyn AssetDynamic>),
     Free(HandleId),
 }
 
 impl AssetLifecycle {
-    pub fn free_asset(&self,id: HandleId){
-        self.sender.send(AssetLifecycleEvent::Free(id)).unwrap();
-    }
-}
-
-impl Default for AssetLifecycle{
-    fn default()->Self{
-        let (sender,receiver) = sync_channel(100);
+    pub(crate) fn new(type_name: &'static str)->Self{
+        // unbounded: the server pushes events from its own systems while the storage system
+        // of the type is the only one draining them
+        let (sender,receiver) = unbounded();
         Self {
             sender,
-            receiver: Mutex::new(receiver),
+            receiver,
+            type_name,
         }
     }
+
+    pub fn free_asset(&self,id: HandleId){
+        self.sender.send(AssetLifecycleEvent::Free(id)).unwrap();
+    }
 }
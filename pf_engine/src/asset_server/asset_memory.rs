@@ -0,0 +1,40 @@
+use serde::{Serialize};
+use std::ops::{Add,AddAssign};
+
+/// Approximate bytes an asset keeps resident.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct AssetMemory{
+    pub cpu_bytes: usize,
+    pub gpu_bytes: usize,
+}
+
+impl AssetMemory{
+    pub fn total(&self)->usize{
+        self.cpu_bytes + self.gpu_bytes
+    }
+}
+
+impl Add for AssetMemory{
+    type Output = Self;
+
+    fn add(self,other: Self)->Self{
+        Self{
+            cpu_bytes: self.cpu_bytes + other.cpu_bytes,
+            gpu_bytes: self.gpu_bytes + other.gpu_bytes,
+        }
+    }
+}
+
+impl AddAssign for AssetMemory{
+    fn add_assign(&mut self,other: Self){
+        *self = *self + other;
+    }
+}
+
+/// Implemented by every type passed to `register_asset`, see `Assets::set_memory_budget`.
+/// Types that stay small can keep the default of zero bytes.
+pub trait AssetMemoryUsage{
+    fn memory_usage(&self)->AssetMemory{
+        AssetMemory::default()
+    }
+}
@@ -1,38 +1,133 @@
-use bevy::utils::AHasher;
-use std::hash::{Hash,Hasher};
+use crate::utils::fnv1a_hash;
+use serde::{Deserialize,Serialize};
 use std::path::{Path};
 
-#[derive(Debug, Hash, Clone)]
+/// Packaged, read only assets. Paths without a scheme belong to it.
+pub const RES_SCHEME: &str = "res";
+/// The app's writable data directory.
+pub const USER_SCHEME: &str = "user";
+/// Files injected at runtime, see `AssetServer::memory_asset_io`.
+pub const MEM_SCHEME: &str = "mem";
+
+/// Splits `scheme://rest` into the scheme and the rest.
+pub fn split_scheme(path: &str)->(&str,&str){
+    match path.split_once("://"){
+        Some((scheme,rest))=>(scheme,rest),
+        None=>(RES_SCHEME,path),
+    }
+}
+
+/// Makes one spelling of every file path: `/` separators, no empty or `.` segments, `..`
+/// applied to the segment before it, a lowercase scheme and no `res://`. Case is kept, the
+/// Android asset manager and most file systems tell `A.png` and `a.png` apart.
+pub fn normalize_path(path: &str)->String{
+    let path = path.replace('\\',"/");
+    let (scheme,rest) = match path.split_once("://"){
+        Some((scheme,rest))=>(scheme.to_lowercase(),rest),
+        None=>(RES_SCHEME.to_string(),path.as_str()),
+    };
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in rest.split('/'){
+        match segment{
+            ""|"."=>{},
+            // a path leaving the root keeps its leading `..`s, see `AssetPath::leaves_root`
+            ".." if segments.last().map_or(false,|last|*last != "..")=>{
+                segments.pop();
+            },
+            _=>segments.push(segment),
+        }
+    }
+    let rest = segments.join("/");
+    if scheme == RES_SCHEME{
+        return rest;
+    }
+    return format!("{}://{}",scheme,rest);
+}
+
+/// Path of an asset file, optionally followed by `#<label>` naming one of the sub-assets its
+/// loader emits, e.g. `level.gltf#Mesh0`. The file part may start with a scheme such as
+/// `user://`, see `VirtualAssetIo`. It is normalized by `normalize_path`, so `res://a.png`,
+/// `./a.png` and `a.png` are the same asset.
+#[derive(Debug, Hash, Clone, PartialEq, Eq)]
 pub struct AssetPath{
     path: String,
+    label: Option<String>,
 }
 
 impl AssetPath{
     pub fn get_id(self)->AssetPathId{
         AssetPathId::from(self)
     }
+    /// The file part, without the label.
     pub fn path(&self)->String{
         self.path.clone()
     }
-    pub fn extension(&self)->String{
-        Path::new(&self.path).extension().unwrap().to_str().unwrap().to_string()
+    /// The path climbs above the root of its scheme, e.g. `user://../x`. The `AssetServer`
+    /// refuses to load or save such paths.
+    pub fn leaves_root(&self)->bool{
+        return split_scheme(&self.path).1.split('/').next() == Some("..");
+    }
+    /// `res` when the path has no scheme.
+    pub fn scheme(&self)->&str{
+        split_scheme(&self.path).0
+    }
+    pub fn label(&self)->Option<&str>{
+        self.label.as_deref()
+    }
+    pub fn with_label<L: Into<String>>(&self,label: L)->AssetPath{
+        AssetPath{
+            path: self.path.clone(),
+            label: Some(label.into()),
+        }
+    }
+    /// Path of the file the labeled asset comes from.
+    pub fn without_label(&self)->AssetPath{
+        AssetPath{
+            path: self.path.clone(),
+            label: None,
+        }
+    }
+    pub fn extension(&self)->Option<String>{
+        Path::new(&self.path).extension().and_then(|ext|ext.to_str()).map(|ext|ext.to_string())
+    }
+    /// Every extension suffix of the file name in lowercase, longest first: `hero.skin.gltf`
+    /// gives `["skin.gltf", "gltf"]`.
+    pub fn extensions(&self)->Vec<String>{
+        let file_name = Path::new(&self.path).file_name().and_then(|name|name.to_str()).unwrap_or("");
+        // the leading dot of a hidden file does not start an extension
+        let file_name = file_name.trim_start_matches('.');
+        return file_name.match_indices('.')
+            .map(|(i,_)|file_name[i+1..].to_lowercase())
+            .filter(|extension|!extension.is_empty())
+            .collect();
     }
 }
 
 impl From<&str> for AssetPath{
     fn from(s: &str)->Self{
-        AssetPath{
-            path: s.to_string()
+        match s.split_once('#'){
+            Some((path,label))=>AssetPath{
+                path: normalize_path(path),
+                label: Some(label.to_string()),
+            },
+            None=>AssetPath{
+                path: normalize_path(s),
+                label: None,
+            },
         }
     }
 }
 
 impl Into<String> for &AssetPath{
     fn into(self)->String{
-        return self.path.clone();
+        match &self.label{
+            Some(label)=>format!("{}#{}",self.path,label),
+            None=>self.path.clone(),
+        }
     }
 }
 
+/// The file part, this is what gets read.
 impl AsRef<Path> for AssetPath{
     fn as_ref(&self)->&Path{
         return self.path.as_str().as_ref();
@@ -40,20 +135,68 @@ impl AsRef<Path> for AssetPath{
 }
 
 
+/// Id of the asset at a path: the 64 bit FNV-1a hash of the UTF-8 bytes of the normalized
+/// path, followed by `#` and the label for a labeled asset. It is the same across runs, builds
+/// and platforms, so save and scene files may store `value` and restore it with `from_value`.
+/// The `AssetServer` fails loading a path whose id another path it saw already has.
 #[derive(
-    Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd
+    Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd, Deserialize, Serialize
 )]
+#[serde(transparent)]
 pub struct AssetPathId (u64);
 
+impl AssetPathId{
+    pub fn value(&self)->u64{
+        self.0
+    }
+
+    pub fn from_value(value: u64)->Self{
+        Self(value)
+    }
+}
+
 impl From<AssetPath> for AssetPathId{
     fn from(asset_path: AssetPath) -> Self{
-        let mut hasher = get_hasher();
-        asset_path.hash(&mut hasher);
-        Self(hasher.finish())
+        let full_path: String = (&asset_path).into();
+        Self(fnv1a_hash(full_path.as_bytes()))
     }
 
 }
-/// this hasher provides consistent results across runs
-pub(crate) fn get_hasher() -> AHasher {
-    AHasher::new_with_keys(42, 23)
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn normalize_path_makes_one_spelling(){
+        assert_eq!(normalize_path("res://textures/./a.png"),"textures/a.png");
+        assert_eq!(normalize_path("textures\\sub//a.png"),"textures/sub/a.png");
+        assert_eq!(normalize_path("textures/sub/../a.png"),"textures/a.png");
+        assert_eq!(normalize_path("USER://saves/Slot1.sav"),"user://saves/Slot1.sav");
+        assert_eq!(normalize_path("/a.png"),"a.png");
+    }
+
+    #[test]
+    fn normalize_path_keeps_leading_parent_segments(){
+        assert_eq!(normalize_path("../a.png"),"../a.png");
+        assert_eq!(normalize_path("a/../../b/../c.png"),"../c.png");
+        assert_eq!(normalize_path("user://../../x"),"user://../../x");
+        assert!(AssetPath::from("user://a/../../x").leaves_root());
+        assert!(!AssetPath::from("user://a/../x").leaves_root());
+    }
+
+    #[test]
+    fn extensions_are_listed_longest_first(){
+        assert_eq!(AssetPath::from("models/foo.mesh.ron").extensions(),vec!["mesh.ron","ron"]);
+        assert_eq!(AssetPath::from("Hero.Skin.GLTF#Mesh0").extensions(),vec!["skin.gltf","gltf"]);
+        assert_eq!(AssetPath::from("user://saves/a.b/slot.sav").extensions(),vec!["sav"]);
+    }
+
+    #[test]
+    fn files_without_extension_have_none(){
+        assert!(AssetPath::from("LICENSE").extensions().is_empty());
+        assert!(AssetPath::from("dir/.hidden").extensions().is_empty());
+        assert!(AssetPath::from("trailing.").extensions().is_empty());
+        assert_eq!(AssetPath::from(".config.ron").extensions(),vec!["ron"]);
+    }
 }
@@ -1,4 +1,4 @@
-use std::sync::mpsc::{Receiver,sync_channel,SyncSender};
+use crossbeam_channel::{Receiver,Sender,unbounded};
 use super::handle::{HandleId};
 use std::collections::{HashMap};
 pub struct AssetRefCounter{
@@ -8,7 +8,8 @@ pub struct AssetRefCounter{
 
 impl Default for AssetRefCounter{
     fn default()->Self{
-        let (sender,receiver) =sync_channel::<RefChange>(100);
+        // unbounded: handles are dropped on the game thread, which is also the only reader
+        let (sender,receiver) =unbounded::<RefChange>();
         Self{
             channel: RefChangeChannel{
                 sender,
@@ -19,11 +20,8 @@ impl Default for AssetRefCounter{
     }
 }
 
-unsafe impl Send for AssetRefCounter{}
-unsafe impl Sync for AssetRefCounter{}
-
 pub struct RefChangeChannel{
-    pub(crate) sender: SyncSender<RefChange>,
+    pub(crate) sender: Sender<RefChange>,
     pub(crate) receiver: Receiver<RefChange>,
 }
 
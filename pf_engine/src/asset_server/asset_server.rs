@@ -1,113 +1,1090 @@
-use super::handle::{Handle,HandleId};
+use super::handle::{Handle,HandleId,HandleUntyped};
 use super::asset_path::{AssetPath,AssetPathId};
 use super::asset_ref_counter::{AssetRefCounter,RefChange};
-use super::handle::{HandleUntyped};
-use super::loader::{AssetLoader};
-use super::assets::{Assets};
+use crossbeam_channel::{Sender,Receiver,TryRecvError,unbounded};
+use super::loader::{AssetLoader,ErasedAssetLoader,LoaderSelection,claims_extension,meta_path};
+use super::load_context::{LoadContext,LoadedAsset};
+use super::assets::{Assets,asset_event_system};
+use super::asset_memory::{AssetMemory,AssetMemoryUsage};
+use super::diagnostics::{AssetDiagnostic,CollectAssetDiagnostics,asset_diagnostics_system};
 use super::asset_stage::{AssetStage};
 use bevy::asset::{Asset};
+use bevy::reflect::TypeUuidDynamic;
 use super::asset_lifecycle::{AssetLifecycle,AssetLifecycleEvent};
-use bevy::prelude::{App,ResMut};
-use std::sync::mpsc::{TryRecvError};
-use std::collections::{HashMap};
+use super::load_state::{LoadState};
+use super::error::{AssetLoadError,AssetSaveError};
+use super::saver::{AssetSaver,ErasedAssetSaver};
+use super::asset_event::{AssetLoadFailed,AssetEvent};
+use bevy::prelude::{App,CoreStage,Res,ResMut,EventWriter,ParallelSystemDescriptorCoercion,SystemLabel};
+use bevy::tasks::{TaskPool,TaskPoolBuilder};
+use std::sync::{Arc};
+use std::time::{Duration,Instant};
+use std::collections::{HashMap,HashSet};
 use bevy::utils::Uuid;
+use crate::log::{error,warn};
+use super::io::{AssetIo,AssetIoError,MemoryAssetIo,VirtualAssetIo,default_asset_io,default_user_asset_io};
+use super::asset_path::{USER_SCHEME,RES_SCHEME};
+use super::pack::{AssetPack,AssetPackError,PackAssetIo};
+use super::cook_manifest::{CookManifest,CookManifestError};
 
 pub struct AssetServer {
     asset_ref_counter : AssetRefCounter,
-    loaders: Vec<Box<dyn AssetLoader>>,
+    loaders: Vec<Arc<dyn ErasedAssetLoader>>,
+    savers: Vec<Box<dyn ErasedAssetSaver>>,
     asset_lifecycles: HashMap<Uuid,AssetLifecycle>, // type_uuid to lifecycle
     source_info: HashMap<AssetPathId,Uuid>,  // asset_path to type_uuid
+    load_states: HashMap<HandleId,LoadState>,
+    task_pool: TaskPool, // runs file io and loaders off the game thread
+    asset_io: Arc<VirtualAssetIo>,
+    cook_manifest: CookManifest,
+    load_result_sender: Sender<LoadResult>,
+    load_result_receiver: Receiver<LoadResult>,
+    parents: HashMap<HandleId,HandleId>, // labeled asset to the asset of its file
+    children: HashMap<HandleId,Vec<HandleId>>, // asset of a file to its labeled assets
+    dependencies: HashMap<HandleId,Vec<HandleUntyped>>, // keeps the dependencies of an asset loaded
+    pending_labels: HashMap<HandleId,Vec<AssetPath>>, // labels requested before their file loaded
+    frame: u64,
+    infos: HashMap<HandleId,AssetInfo>, // paths of live assets, for `AssetDiagnostics`
+    registered_paths: HashMap<HandleId,AssetPath>, // every path ever loaded, never pruned
+}
+
+struct AssetInfo {
+    path: AssetPath,
+    load_started: Option<Instant>,
+    load_duration: Option<Duration>,
+}
+
+impl AssetInfo {
+    fn new(path: AssetPath)->Self{
+        Self{
+            path,
+            load_started: None,
+            load_duration: None,
+        }
+    }
+}
+
+/// What a loader task sends back to the game thread.
+enum LoadResult {
+    Loaded{
+        asset_path: AssetPath,
+        type_uuid: Option<Uuid>,
+        loaded: LoadedAsset,
+    },
+    Failed{
+        asset_path: AssetPath,
+        error: AssetLoadError,
+    },
+    /// Refused before it started, see `AssetServer::reject_load`.
+    Rejected{
+        id: HandleId,
+        error: AssetLoadError,
+    },
 }
 
 impl Default for AssetServer {
     fn default() ->Self{
+        let asset_server = Self::new(default_asset_io());
+        asset_server.mount(USER_SCHEME,"",default_user_asset_io(),0);
+        asset_server
+    }
+}
+
+impl AssetServer {
+    /// `asset_io` serves `res://` paths and paths without a scheme, see `mount` for more.
+    pub fn new(asset_io: Box<dyn AssetIo>)->Self{
+        // unbounded: loader threads must never block on a full channel while the game
+        // thread is the only one draining it
+        let (load_result_sender,load_result_receiver) = unbounded();
         Self{
             asset_ref_counter: Default::default(),
             loaders:Default::default(),
+            savers: Vec::new(),
             asset_lifecycles: Default::default(),
             source_info: Default::default(),
+            load_states: Default::default(),
+            task_pool: TaskPoolBuilder::new().thread_name("asset loader".to_string()).build(),
+            asset_io: Arc::new(VirtualAssetIo::new(asset_io)),
+            cook_manifest: Default::default(),
+            load_result_sender,
+            load_result_receiver,
+            parents: Default::default(),
+            children: Default::default(),
+            dependencies: Default::default(),
+            pending_labels: Default::default(),
+            frame: 0,
+            infos: Default::default(),
+            registered_paths: Default::default(),
         }
     }
-}
 
+    /// Returns a handle right away, the file is read and decoded on the loader pool.
+    /// The asset shows up in `Assets<T>` during `AssetStage::UpdateAssets` once it is ready,
+    /// use `get_load_state` to follow it. A path that is already loaded or in flight is not
+    /// read again, the returned handle shares the existing asset. A labeled path such as
+    /// `level.gltf#Mesh0` loads the whole file.
+    pub fn load<T:Asset,P: Into<AssetPath>>(&mut self, path: P)->Handle<T>{
+        let asset_path = self.cooked_path(path.into());
+        let handle_id = match self.register_path(&asset_path){
+            Ok(handle_id)=>handle_id,
+            Err(error)=>return self.reject_load(Some(T::TYPE_UUID),error).typed(),
+        };
+        let handle = self.strong_handle(handle_id).typed();
+        self.load_path(asset_path,Some(T::TYPE_UUID));
+        return handle;
+    }
 
-impl AssetServer{
-    pub fn load<T:Asset,P: Into<AssetPath>>(&mut self, path: P,assets: &mut Assets<T>)->Handle<T>{
-        let asset_path = path.into();
+    /// Like `load`, for whichever asset type the loader of the path produces.
+    pub fn load_untyped<P: Into<AssetPath>>(&mut self, path: P)->HandleUntyped{
+        let asset_path = self.cooked_path(path.into());
+        let handle_id = match self.register_path(&asset_path){
+            Ok(handle_id)=>handle_id,
+            Err(error)=>return self.reject_load(None,error),
+        };
+        let handle = self.strong_handle(handle_id);
+        self.load_path(asset_path,None);
+        return handle;
+    }
+
+    /// Like `load`, but always reads and decodes the file again. The new asset replaces the
+    /// old one under the same handle id.
+    pub fn reload<T:Asset,P: Into<AssetPath>>(&mut self, path: P)->Handle<T>{
+        let asset_path = self.cooked_path(path.into());
+        let handle_id = match self.register_path(&asset_path){
+            Ok(handle_id)=>handle_id,
+            Err(error)=>return self.reject_load(Some(T::TYPE_UUID),error).typed(),
+        };
+        let handle = self.strong_handle(handle_id).typed();
+        if asset_path.label().is_some(){
+            let file_path = asset_path.without_label();
+            let type_uuid = self.source_info.get(&AssetPathId::from(file_path.clone())).copied();
+            self.spawn_load(file_path,type_uuid);
+        }else{
+            self.spawn_load(asset_path,Some(T::TYPE_UUID));
+        }
+        return handle;
+    }
+
+    fn load_path(&mut self,asset_path: AssetPath,type_uuid: Option<Uuid>){
+        let handle_id = HandleId::from(asset_path.clone());
+        match self.get_load_state(handle_id){
+            LoadState::Loading | LoadState::Loaded => return,
+            LoadState::NotLoaded | LoadState::Failed => {},
+        }
+        if asset_path.label().is_none(){
+            self.spawn_load(asset_path,type_uuid);
+            return;
+        }
+
+        // the labeled asset arrives with its file, whose own type is not known here
+        let file_path = asset_path.without_label();
+        let file_id = HandleId::from(file_path.clone());
+        self.link_child(handle_id,file_id);
+        self.infos.entry(handle_id).or_insert_with(||AssetInfo::new(asset_path.clone()));
+        self.load_states.insert(handle_id,LoadState::Loading);
+        match self.get_load_state(file_id){
+            LoadState::Loading => {
+                self.pending_labels.entry(file_id).or_default().push(asset_path);
+            },
+            // every label of a loaded file arrived with it
+            LoadState::Loaded => {
+                let label = asset_path.label().unwrap_or_default().to_string();
+                let error = AssetLoadError::MissingLabel{path:file_path,label};
+                let _ = self.load_result_sender.send(LoadResult::Failed{asset_path,error});
+            },
+            LoadState::NotLoaded | LoadState::Failed => {
+                self.pending_labels.entry(file_id).or_default().push(asset_path);
+                let file_type_uuid = self.source_info.get(&AssetPathId::from(file_path.clone())).copied();
+                self.spawn_load(file_path,file_type_uuid);
+            },
+        }
+    }
+
+    /// Loads the file at `asset_path`, which has no label. `type_uuid` is the asset type the
+    /// caller expects, if it knows one.
+    fn spawn_load(&mut self,asset_path: AssetPath,type_uuid: Option<Uuid>){
         let handle_id = HandleId::from(asset_path.clone());
-        let extension = asset_path.extension();
-        let loader = self.loaders.iter().find(|loader| loader.extensions().iter().find(|&&ext| ext==extension ).is_some()).unwrap();
-        let asset_any = loader.load(&asset_path);
-        let asset = asset_any.downcast::<T>().unwrap();
-        assets.insert(handle_id,*asset);
-        let asset_path_id = AssetPathId::from(asset_path.clone());
-        self.source_info.insert(asset_path_id,T::TYPE_UUID);
-        return HandleUntyped::strong(handle_id,self.asset_ref_counter.channel.sender.clone()).typed();
+        if let Some(type_uuid) = type_uuid{
+            if !self.asset_lifecycles.contains_key(&type_uuid){
+                // reported by `process_load_results`, like failures on the loader pool
+                if self.get_load_state(handle_id) != LoadState::Loaded{
+                    self.load_states.insert(handle_id,LoadState::Loading);
+                }
+                let error = AssetLoadError::UnregisteredRequestedType{path:asset_path.clone(),type_uuid};
+                let _ = self.load_result_sender.send(LoadResult::Failed{asset_path,error});
+                return;
+            }
+            self.source_info.insert(AssetPathId::from(asset_path.clone()),type_uuid);
+        }
+
+        self.infos.entry(handle_id)
+            .or_insert_with(||AssetInfo::new(asset_path.clone()))
+            .load_started = Some(Instant::now());
+
+        let asset_io = self.asset_io.clone();
+        let loaders = self.find_loaders(&asset_path,type_uuid);
+        let sender = self.load_result_sender.clone();
+
+        // a reloaded asset stays usable until its replacement lands
+        if self.get_load_state(handle_id) != LoadState::Loaded{
+            self.load_states.insert(handle_id,LoadState::Loading);
+        }
+        self.task_pool.spawn(async move {
+            let result = match load_with_io(&*asset_io,loaders,&asset_path){
+                Ok(loaded)=>LoadResult::Loaded{asset_path,type_uuid,loaded},
+                Err(error)=>LoadResult::Failed{asset_path,error},
+            };
+            // the server owns the receiver, a send error only means the app is shutting down
+            let _ = sender.send(result);
+        }).detach();
+    }
+
+    pub fn get_load_state<H: Into<HandleId>>(&self,handle: H)->LoadState{
+        return self.load_states.get(&handle.into()).copied().unwrap_or(LoadState::NotLoaded);
+    }
+
+    /// Like `get_load_state`, but only `Loaded` once every dependency the loaders declared,
+    /// and theirs, is loaded too. `Failed` as soon as any of them failed.
+    pub fn get_dependency_load_state<H: Into<HandleId>>(&self,handle: H)->LoadState{
+        let handle_id = handle.into();
+        let own_state = self.get_load_state(handle_id);
+        if own_state != LoadState::Loaded{
+            return own_state;
+        }
+        let mut state = LoadState::Loaded;
+        let mut visited = HashSet::new();
+        let mut pending = vec![handle_id];
+        while let Some(id) = pending.pop(){
+            // dependencies are declared by the loader of the file, labeled assets share them
+            let id = self.parents.get(&id).copied().unwrap_or(id);
+            if !visited.insert(id){
+                continue;
+            }
+            for dependency in self.dependencies.get(&id).into_iter().flatten(){
+                match self.get_load_state(dependency.id()){
+                    LoadState::Failed => return LoadState::Failed,
+                    LoadState::Loaded => pending.push(dependency.id()),
+                    LoadState::Loading | LoadState::NotLoaded => state = LoadState::Loading,
+                }
+            }
+        }
+        return state;
+    }
+
+    /// The longest extension of the path that a loader producing `type_uuid` claims decides,
+    /// any loader does when the type is not known. When none does, or several loaders claim
+    /// it, the choice is left to sniffing the bytes.
+    fn find_loaders(&self,asset_path: &AssetPath,type_uuid: Option<Uuid>)->LoaderSelection{
+        let of_type: Vec<_> = self.loaders.iter()
+            .filter(|loader|type_uuid.map_or(true,|type_uuid|loader.asset_type_uuid() == type_uuid))
+            .cloned()
+            .collect();
+        let extensions = asset_path.extensions();
+        let mut claimed_by_other_type = false;
+        for extension in extensions.iter(){
+            let mut claiming: Vec<_> = of_type.iter()
+                .filter(|loader|claims_extension(&***loader,extension))
+                .cloned()
+                .collect();
+            match claiming.len(){
+                0=>{
+                    claimed_by_other_type |= self.loaders.iter().any(|loader|claims_extension(&**loader,extension));
+                    continue;
+                },
+                1=>return LoaderSelection::Loader(claiming.pop().unwrap()),
+                _=>{
+                    let error = AssetLoadError::AmbiguousLoader{
+                        path:asset_path.clone(),
+                        loaders:claiming.iter().map(|loader|loader.type_name()).collect(),
+                    };
+                    return LoaderSelection::Sniff{candidates:claiming,error};
+                },
+            }
+        }
+        let error = match (type_uuid,extensions.first()){
+            (Some(type_uuid),_) if claimed_by_other_type=>AssetLoadError::TypeMismatch{
+                path:asset_path.clone(),
+                expected:self.asset_type_name(type_uuid),
+            },
+            (_,Some(extension))=>AssetLoadError::NoLoaderForExtension{path:asset_path.clone(),extension:extension.clone()},
+            (_,None)=>AssetLoadError::MissingExtension(asset_path.clone()),
+        };
+        return LoaderSelection::Sniff{candidates:of_type,error};
+    }
+
+    fn asset_type_name(&self,type_uuid: Uuid)->&'static str{
+        return self.asset_lifecycles.get(&type_uuid).map_or("?",|lifecycle|lifecycle.type_name);
+    }
+
+    pub fn asset_io(&self)->&dyn AssetIo{
+        &*self.asset_io
+    }
+
+    /// Files injected at runtime, served at `mem://`.
+    pub fn memory_asset_io(&self)->&MemoryAssetIo{
+        self.asset_io.memory()
+    }
+
+    /// Serves `scheme://<prefix>/<path>` from `<path>` of `io`, in front of the ios already
+    /// mounted there with the same or a lower priority; a path missing from `io` falls
+    /// through to them. Assets that are already loaded keep their bytes.
+    pub fn mount<P: Into<std::path::PathBuf>>(&self,scheme: &str,prefix: P,io: Box<dyn AssetIo>,priority: i32){
+        self.asset_io.mount(scheme,prefix,Arc::from(io),priority);
+    }
+
+    /// Removes every io mounted at `scheme://<prefix>`, returns whether there was one.
+    pub fn unmount<P: AsRef<std::path::Path>>(&self,scheme: &str,prefix: P)->bool{
+        self.asset_io.unmount(scheme,prefix)
+    }
+
+    /// Serves the files of `pack` at `res://` in front of the ios mounted there with priority 0.
+    pub fn mount_pack(&self,pack: AssetPack){
+        self.mount(RES_SCHEME,"",Box::new(PackAssetIo::new(pack,None)),0);
+    }
+
+    /// Opens the pack at `path`, which may be in any mounted scheme, and mounts it. Blocks
+    /// until its index is read, entries are read from the pack as they are loaded.
+    pub fn mount_pack_file<P: AsRef<std::path::Path>>(&self,path: P)->Result<(),AssetPackError>{
+        let pack = AssetPack::open(&*self.asset_io,path)?;
+        self.mount_pack(pack);
+        return Ok(());
+    }
+
+    /// Makes `load` and `reload` read the cooked file of a source path listed in `manifest`.
+    /// Handles are keyed by the cooked path.
+    pub fn set_cook_manifest(&mut self,manifest: CookManifest){
+        self.cook_manifest = manifest;
+    }
+
+    /// Reads the manifest the asset processor wrote into the asset root and uses it, blocks
+    /// until it is read.
+    pub fn load_cook_manifest(&mut self)->Result<(),CookManifestError>{
+        let manifest = CookManifest::open(&*self.asset_io)?;
+        self.set_cook_manifest(manifest);
+        return Ok(());
+    }
+
+    fn cooked_path(&self,asset_path: AssetPath)->AssetPath{
+        let cooked = match self.cook_manifest.cooked_path(&asset_path.path()){
+            Some(cooked)=>AssetPath::from(cooked),
+            None=>return asset_path,
+        };
+        match asset_path.label(){
+            Some(label)=>cooked.with_label(label),
+            None=>cooked,
+        }
+    }
+
+    /// Starts reloading loaded assets whose source is modified, if the `AssetIo` can watch
+    /// for changes. The replaced asset keeps its handle id; everything derived from the old
+    /// one, such as `Texture::gpu_texture`, starts over from the new asset.
+    pub fn watch_for_changes(&self)->Result<(),AssetIoError>{
+        self.asset_io.watch_for_changes()
+    }
+
+    fn reload_changed_assets(&mut self){
+        for path in self.asset_io.changed_paths(){
+            let asset_path = AssetPath::from(path.to_string_lossy().replace('\\',"/").as_str());
+            // only assets that are still alive are reloaded
+            if self.get_load_state(HandleId::from(asset_path.clone())) == LoadState::NotLoaded{
+                continue;
+            }
+            let type_uuid = self.source_info.get(&AssetPathId::from(asset_path.clone())).copied();
+            self.spawn_load(asset_path,type_uuid);
+        }
+    }
+
+    /// Hands what the loader tasks finished to the `Assets<T>` lifecycles, and starts loading
+    /// the dependencies they declared.
+    fn process_load_results(&mut self)->Vec<AssetLoadFailed>{
+        let mut failed = Vec::new();
+        // the server owns the sender too, the channel is never disconnected
+        while let Ok(result) = self.load_result_receiver.try_recv(){
+            match result{
+                LoadResult::Loaded{asset_path,type_uuid,loaded}=>{
+                    if let Err(error) = self.add_loaded_asset(&asset_path,type_uuid,loaded,&mut failed){
+                        self.fail_load(asset_path,error,&mut failed);
+                    }
+                },
+                LoadResult::Failed{asset_path,error}=>self.fail_load(asset_path,error,&mut failed),
+                // the handle was dropped before the failure was reported
+                LoadResult::Rejected{id,error} if self.load_states.contains_key(&id)=>{
+                    error!("{}",error);
+                    failed.push(AssetLoadFailed{id,error});
+                },
+                LoadResult::Rejected{..}=>{},
+            }
+        }
+        return failed;
+    }
+
+    fn add_loaded_asset(&mut self,asset_path: &AssetPath,type_uuid: Option<Uuid>,loaded: LoadedAsset,failed: &mut Vec<AssetLoadFailed>)->Result<(),AssetLoadError>{
+        let handle_id = HandleId::from(asset_path.clone());
+        // the asset was freed while it was still loading
+        if !self.load_states.contains_key(&handle_id){
+            return Ok(());
+        }
+        let LoadedAsset{asset,labeled_assets,dependencies} = loaded;
+        if let Some(type_uuid) = type_uuid{
+            if asset.type_uuid() != type_uuid{
+                return Err(AssetLoadError::TypeMismatch{path:asset_path.clone(),expected:self.asset_type_name(type_uuid)});
+            }
+        }
+        // nothing is handed out before every produced type is known to be registered
+        let sender = self.lifecycle_sender(asset_path,asset.type_uuid(),asset.type_name())?;
+        let mut labeled = Vec::new();
+        for (label,labeled_asset) in labeled_assets{
+            let label_path = asset_path.with_label(label);
+            let label_sender = self.lifecycle_sender(&label_path,labeled_asset.type_uuid(),labeled_asset.type_name())?;
+            let child_id = self.register_path(&label_path)?;
+            labeled.push((label_path,child_id,labeled_asset,label_sender));
+        }
+
+        self.source_info.insert(AssetPathId::from(asset_path.clone()),asset.type_uuid());
+        sender.send(AssetLifecycleEvent::Create(handle_id,asset)).unwrap();
+        let load_duration = self.finish_load_timing(handle_id);
+
+        // a label that is gone after a reload keeps its old asset until the file is freed
+        let mut children = self.children.remove(&handle_id).unwrap_or_default();
+        for (label_path,child_id,labeled_asset,label_sender) in labeled{
+            self.source_info.insert(AssetPathId::from(label_path.clone()),labeled_asset.type_uuid());
+            self.link_child(child_id,handle_id);
+            if self.get_load_state(child_id) != LoadState::Loaded{
+                self.load_states.insert(child_id,LoadState::Loading);
+            }
+            label_sender.send(AssetLifecycleEvent::Create(child_id,labeled_asset)).unwrap();
+            if let Some(child_info) = self.infos.get_mut(&child_id){
+                child_info.load_duration = load_duration;
+            }
+            if !children.contains(&child_id){
+                children.push(child_id);
+            }
+        }
+        for label_path in self.pending_labels.remove(&handle_id).unwrap_or_default(){
+            let child_id = HandleId::from(label_path.clone());
+            if !children.contains(&child_id) && self.load_states.contains_key(&child_id){
+                let label = label_path.label().unwrap_or_default().to_string();
+                let error = AssetLoadError::MissingLabel{path:asset_path.clone(),label};
+                self.fail_load(label_path,error,failed);
+            }
+        }
+        self.children.insert(handle_id,children);
+
+        // the handles of a previous load are only dropped now, shared dependencies stay loaded
+        let dependencies = dependencies.into_iter().map(|path|self.load_untyped(path)).collect();
+        self.dependencies.insert(handle_id,dependencies);
+        return Ok(());
+    }
+
+    fn lifecycle_sender(&self,asset_path: &AssetPath,type_uuid: Uuid,type_name: &'static str)->Result<Sender<AssetLifecycleEvent>,AssetLoadError>{
+        return self.asset_lifecycles.get(&type_uuid)
+            .map(|lifecycle|lifecycle.sender.clone())
+            .ok_or(AssetLoadError::UnregisteredAssetType{path:asset_path.clone(),type_name});
+    }
+
+    fn fail_load(&mut self,asset_path: AssetPath,error: AssetLoadError,failed: &mut Vec<AssetLoadFailed>){
+        let id = HandleId::from(asset_path.clone());
+        // the asset was freed while it was still loading
+        if !self.load_states.contains_key(&id){
+            return;
+        }
+        error!("{}",error);
+        self.load_states.insert(id,LoadState::Failed);
+        self.finish_load_timing(id);
+        for label_path in self.pending_labels.remove(&id).unwrap_or_default(){
+            let child_id = HandleId::from(label_path);
+            if self.load_states.contains_key(&child_id){
+                self.load_states.insert(child_id,LoadState::Failed);
+            }
+        }
+        failed.push(AssetLoadFailed{id,error});
+    }
+
+    /// Records which path the id belongs to. Fails for a path leaving the root of its scheme,
+    /// and when another path already has the id, the two would share one asset otherwise.
+    /// Paths are remembered for the lifetime of the server, also after their asset is freed,
+    /// so ids saved while one path was loaded never come back for another.
+    fn register_path(&mut self,asset_path: &AssetPath)->Result<HandleId,AssetLoadError>{
+        if asset_path.leaves_root(){
+            return Err(AssetLoadError::PathOutsideRoot(asset_path.clone()));
+        }
+        let handle_id = HandleId::from(asset_path.clone());
+        let registered = self.registered_paths.entry(handle_id).or_insert_with(||asset_path.clone());
+        if registered != asset_path{
+            return Err(AssetLoadError::IdCollision{path:asset_path.clone(),other:registered.clone()});
+        }
+        self.infos.entry(handle_id).or_insert_with(||AssetInfo::new(asset_path.clone()));
+        return Ok(handle_id);
+    }
+
+    /// Fails a load before it starts. The handle gets an id of its own, the asset of a colliding
+    /// path keeps its state.
+    fn reject_load(&mut self,type_uuid: Option<Uuid>,error: AssetLoadError)->HandleUntyped{
+        let id = HandleId::generated_with_type_uuid(type_uuid.unwrap_or_else(Uuid::nil));
+        self.load_states.insert(id,LoadState::Failed);
+        let _ = self.load_result_sender.send(LoadResult::Rejected{id,error});
+        return self.strong_handle(id);
+    }
+
+    /// Path of an asset the server loaded, e.g. to turn an id restored from a save file back
+    /// into a path. `None` for assets added at runtime and once the asset is freed.
+    pub fn get_handle_path<H: Into<HandleId>>(&self,handle: H)->Option<&AssetPath>{
+        return self.infos.get(&handle.into()).map(|info|&info.path);
+    }
+
+    fn finish_load_timing(&mut self,id: HandleId)->Option<Duration>{
+        let info = self.infos.get_mut(&id)?;
+        info.load_duration = info.load_started.take().map(|started|started.elapsed());
+        return info.load_duration;
+    }
+
+    fn link_child(&mut self,child: HandleId,parent: HandleId){
+        // a labeled asset referenced before the link existed starts counting for its file now
+        if self.parents.insert(child,parent).is_none() && self.asset_ref_counter.ref_counts.contains_key(&child){
+            self.add_ref(parent);
+        }
+    }
+
+    fn asset_type_uuid(&self,id: HandleId)->Option<Uuid>{
+        match id{
+            HandleId::Id(type_uuid,_)=>Some(type_uuid),
+            HandleId::AssetPathId(asset_path_id)=>self.source_info.get(&asset_path_id).copied(),
+        }
+    }
+
+    /// Counts frames, advanced during `CoreStage::PreUpdate`. Stamped on the assets accessed
+    /// through `Assets<T>`, see `Assets::last_access`.
+    pub fn frame(&self)->u64{
+        self.frame
+    }
+
+    pub(crate) fn asset_diagnostic(&self,id: HandleId,type_uuid: Option<Uuid>,memory: AssetMemory)->AssetDiagnostic{
+        let info = self.infos.get(&id);
+        let type_uuid = type_uuid.or_else(||self.asset_type_uuid(id));
+        AssetDiagnostic{
+            id,
+            path: info.map(|info|(&info.path).into()),
+            type_uuid: type_uuid.map(|type_uuid|type_uuid.to_string()),
+            type_name: type_uuid.and_then(|type_uuid|self.asset_lifecycles.get(&type_uuid)).map(|lifecycle|lifecycle.type_name),
+            ref_count: self.asset_ref_counter.ref_counts.get(&id).copied().unwrap_or(0),
+            load_state: self.get_load_state(id),
+            memory,
+            load_duration_ms: info.and_then(|info|info.load_duration).map(|duration|duration.as_secs_f64()*1000.0),
+        }
+    }
+
+    /// Assets that are loading or failed, the stored ones are listed per `Assets<T>`.
+    pub(crate) fn unstored_asset_diagnostics(&self)->Vec<AssetDiagnostic>{
+        return self.load_states.iter()
+            .filter(|(_,&state)|state == LoadState::Loading || state == LoadState::Failed)
+            .map(|(&id,_)|self.asset_diagnostic(id,None,AssetMemory::default()))
+            .collect();
+    }
+
+    /// Forgets the infos of assets that are neither loaded nor referenced anymore, their paths
+    /// stay registered, see `register_path`.
+    fn prune_asset_infos(&mut self){
+        let load_states = &self.load_states;
+        let ref_counts = &self.asset_ref_counter.ref_counts;
+        self.infos.retain(|id,_|load_states.contains_key(id) || ref_counts.contains_key(id));
+    }
+
+    /// Whether the asset may go to keep `Assets<T>` within its memory budget: nothing holds it
+    /// strongly. Held assets are never evicted, nothing would load them back for their handles.
+    fn is_evictable(&self,id: HandleId)->bool{
+        // labeled assets only go with their file
+        if self.parents.contains_key(&id){
+            return false;
+        }
+        return !self.asset_ref_counter.ref_counts.contains_key(&id);
+    }
+
+    fn evict_over_budget<T:Asset+AssetMemoryUsage>(&mut self,assets: &mut Assets<T>){
+        let budget = match assets.memory_budget(){
+            Some(budget)=>budget,
+            None=>return,
+        };
+        let mut usage = assets.memory_usage().total();
+        if usage <= budget{
+            return;
+        }
+        for (last_access,id) in assets.access_order(){
+            if usage <= budget || last_access >= self.frame{
+                break;
+            }
+            if !self.is_evictable(id){
+                continue;
+            }
+            usage = usage.saturating_sub(assets.asset_memory_usage(&id).total());
+            assets.evict(id);
+            // the next `load` of the path reads it again
+            self.load_states.remove(&id);
+            self.release_asset(id);
+        }
+    }
+
+    /// Counts the reference right away instead of in the next `CoreStage::PreUpdate`, so a
+    /// `Free` of the asset queued earlier in the frame sees it and keeps the asset.
+    pub(crate) fn strong_handle(&mut self,id: HandleId)->HandleUntyped{
+        self.add_ref(id);
+        return HandleUntyped::counted(id,self.ref_change_sender());
+    }
+
+    pub(crate) fn ref_change_sender(&self)->Sender<RefChange>{
+        self.asset_ref_counter.channel.sender.clone()
+    }
+
+    /// Loaders may share an extension, files with it are then told apart by `AssetLoader::sniff`.
+    pub fn add_loader<L: AssetLoader>(&mut self,loader: L){
+        for &extension in AssetLoader::extensions(&loader){
+            for other in self.loaders.iter().filter(|other|claims_extension(&***other,extension)){
+                warn!("{} and {} both claim extension \"{}\", its files must be told apart by sniffing",
+                    other.type_name(),std::any::type_name::<L>(),extension);
+            }
+        }
+        self.loaders.push(Arc::new(loader));
+    }
+
+    /// A later saver wins over earlier ones of the same asset type and extension.
+    pub fn add_saver<S: AssetSaver>(&mut self,saver: S){
+        self.savers.insert(0,Box::new(saver));
+    }
+
+    /// Encodes the asset behind `handle` with the saver of `T` picked by the extension of
+    /// `path`, and writes it through the server's `AssetIo`. Blocks until the file is written.
+    /// A loaded asset at `path` is not replaced unless the io reports the change.
+    pub fn save<T:Asset,P: Into<AssetPath>>(&self,assets: &Assets<T>,handle: &Handle<T>,path: P)->Result<(),AssetSaveError>{
+        let asset_path = path.into();
+        if asset_path.leaves_root(){
+            return Err(AssetSaveError::PathOutsideRoot(asset_path));
+        }
+        let asset = assets.get_asset(handle).ok_or_else(||AssetSaveError::MissingAsset(asset_path.clone()))?;
+        let saver = self.find_saver(T::TYPE_UUID,&asset_path).ok_or_else(||AssetSaveError::NoSaver{
+            path:asset_path.clone(),
+            type_name:std::any::type_name::<T>(),
+        })?;
+        let bytes = saver.save(asset,&asset_path)?;
+        self.asset_io.write(asset_path.as_ref(),&bytes)
+            .map_err(|error|AssetSaveError::Io{path:asset_path.clone(),error})?;
+        return Ok(());
     }
 
-    pub fn add_loader(&mut self,loader: Box<dyn AssetLoader>){
-        self.loaders.push(loader);
+    fn find_saver(&self,type_uuid: Uuid,asset_path: &AssetPath)->Option<&dyn ErasedAssetSaver>{
+        for extension in asset_path.extensions().iter(){
+            let saver = self.savers.iter().find(|saver|{
+                saver.asset_type_uuid() == type_uuid
+                    && saver.extensions().iter().any(|ext|ext.eq_ignore_ascii_case(extension))
+            });
+            if let Some(saver) = saver{
+                return Some(&**saver);
+            }
+        }
+        return None;
     }
 
     pub(crate) fn free_unused_assets_system(&mut self) {
-        let receiver = &self.asset_ref_counter.channel.receiver;
         loop{
-            let ref_change = match receiver.try_recv(){
+            let ref_change = match self.asset_ref_counter.channel.receiver.try_recv(){
                 Ok(ref_change)=>ref_change,
                 Err(TryRecvError::Empty)=>break,
                 Err(TryRecvError::Disconnected)=>panic!("unreachable")
             };
             match ref_change{
-                RefChange::Increment(id)=> {
-                    *self.asset_ref_counter.ref_counts.entry(id).or_insert(0) +=1 ;
-                },
-                RefChange::Decrement(id)=> {
-                    let entry = self.asset_ref_counter.ref_counts.entry(id).or_insert(0);
-                    *entry -=1;
-                    if *entry == 0 {
-                        // remove asset
-                        let type_uuid = self.source_info.get(&id.0).unwrap();
-                        if let Some(asset_lifecycle) = self.asset_lifecycles.get(type_uuid){
-                            asset_lifecycle.free_asset(id);
-                        }
-                    }
-                },
+                RefChange::Increment(id)=>self.add_ref(id),
+                RefChange::Decrement(id)=>self.remove_ref(id),
             }
         }
     }
 
+    fn add_ref(&mut self,id: HandleId){
+        let count = self.asset_ref_counter.ref_counts.entry(id).or_insert(0);
+        *count +=1;
+        // a referenced labeled asset keeps the asset of its file alive
+        if *count == 1{
+            if let Some(&parent) = self.parents.get(&id){
+                self.add_ref(parent);
+            }
+        }
+    }
+
+    fn remove_ref(&mut self,id: HandleId){
+        let count = self.asset_ref_counter.ref_counts.entry(id).or_insert(0);
+        *count -=1;
+        if *count == 0 {
+            self.asset_ref_counter.ref_counts.remove(&id);
+            match self.parents.get(&id).copied(){
+                // labeled assets live as long as the asset of their file
+                Some(parent)=>self.remove_ref(parent),
+                None=>self.free_asset(id),
+            }
+        }
+    }
+
+    fn free_asset(&mut self,id: HandleId){
+        let asset_lifecycle = self.asset_type_uuid(id).and_then(|type_uuid|self.asset_lifecycles.get(&type_uuid));
+        match asset_lifecycle{
+            // released when the lifecycle frees it, unless a new strong handle showed up by then
+            Some(asset_lifecycle)=>asset_lifecycle.free_asset(id),
+            // still loading with no known type, dropping the state discards the result
+            None=>{
+                self.load_states.remove(&id);
+                self.release_asset(id);
+            },
+        }
+    }
+
+    /// Drops the labeled assets and dependencies of an asset that is gone.
+    fn release_asset(&mut self,id: HandleId){
+        for label_path in self.pending_labels.remove(&id).unwrap_or_default(){
+            let child_id = HandleId::from(label_path);
+            self.parents.remove(&child_id);
+            self.load_states.remove(&child_id);
+        }
+        for child_id in self.children.remove(&id).unwrap_or_default(){
+            self.parents.remove(&child_id);
+            self.free_asset(child_id);
+        }
+        // the dependency handles drop here, their counts are updated in the next `CoreStage::PreUpdate`
+        self.dependencies.remove(&id);
+    }
+
+}
+
+/// Reads the asset and its optional `.meta` file, then runs the loader. Called on the loader pool.
+fn load_with_io(asset_io: &dyn AssetIo,loaders: LoaderSelection,asset_path: &AssetPath)->Result<LoadedAsset,AssetLoadError>{
+    let bytes = asset_io.read(asset_path.as_ref())
+        .map_err(|error|AssetLoadError::Io{path:asset_path.clone(),error})?;
+    let loader = loaders.resolve(&bytes,asset_path)?;
+    let meta = match asset_io.read(std::path::Path::new(&meta_path(asset_path))){
+        Ok(meta)=>Some(meta),
+        Err(AssetIoError::NotFound(_))=>None,
+        Err(error)=>return Err(AssetLoadError::Io{path:asset_path.clone(),error}),
+    };
+    let mut load_context = LoadContext::new(asset_path);
+    let asset = loader.load(&bytes,meta.as_deref(),&mut load_context)?;
+    return Ok(load_context.finish(asset));
 }
 
+#[derive(SystemLabel,Clone,Debug,PartialEq,Eq,Hash)]
+pub(crate) struct FreeUnusedAssets;
+
 pub(crate) fn free_unused_assets_system(mut asset_server: ResMut<AssetServer>){
+    asset_server.frame += 1;
     asset_server.free_unused_assets_system();
+    asset_server.prune_asset_infos();
+}
+
+pub(crate) fn asset_io_watcher_system(mut asset_server: ResMut<AssetServer>){
+    asset_server.reload_changed_assets();
+}
+
+pub(crate) fn load_results_system(mut asset_server: ResMut<AssetServer>,mut load_failed_events: EventWriter<AssetLoadFailed>){
+    load_failed_events.send_batch(asset_server.process_load_results().into_iter());
 }
 
-pub fn register_asset<T:Asset>(app:&mut App) {
-        app.insert_resource(Assets::<T>::new());
+pub fn register_asset<T:Asset+AssetMemoryUsage>(app:&mut App) {
         let mut asset_server = app.world.get_resource_mut::<AssetServer>().unwrap();
-        asset_server.asset_lifecycles.insert(T::TYPE_UUID,AssetLifecycle::default());
+        asset_server.asset_lifecycles.insert(T::TYPE_UUID,AssetLifecycle::new(std::any::type_name::<T>()));
+        let assets = Assets::<T>::new(asset_server.ref_change_sender());
+        app.insert_resource(assets);
+        app.add_event::<AssetEvent<T>>();
+        // accesses during `CoreStage::Update` are stamped with the frame they happen in
+        app.add_system_to_stage(CoreStage::PreUpdate, sync_asset_frame_system::<T>.after(FreeUnusedAssets));
         app.add_system_to_stage(AssetStage::UpdateAssets, update_asset_storage_system::<T>);
+        app.add_system_to_stage(AssetStage::AssetEvents, asset_event_system::<T>);
+        app.add_system_to_stage(AssetStage::AssetEvents, asset_diagnostics_system::<T>.label(CollectAssetDiagnostics));
+}
+
+fn sync_asset_frame_system<T:Asset>(asset_server: Res<AssetServer>,mut assets: ResMut<Assets<T>>){
+    assets.frame = asset_server.frame;
 }
 
-fn update_asset_storage_system<T:Asset>(asset_server: ResMut<AssetServer>,mut assets: ResMut<Assets<T>>){
-    let lifecycle = asset_server.asset_lifecycles.get(&T::TYPE_UUID).unwrap();
-    let receiver = lifecycle.receiver.lock().unwrap();
+fn update_asset_storage_system<T:Asset+AssetMemoryUsage>(
+    mut asset_server: ResMut<AssetServer>,
+    mut assets: ResMut<Assets<T>>,
+    ){
+    let asset_server = &mut *asset_server;
+    let receiver = asset_server.asset_lifecycles.get(&T::TYPE_UUID).unwrap().receiver.clone();
     loop {
-        let handle_id =  match receiver.try_recv(){
-            Ok(handle_id)=>handle_id,
+        let event =  match receiver.try_recv(){
+            Ok(event)=>event,
             Err(TryRecvError::Empty)=>break,
             Err(TryRecvError::Disconnected)=>panic!("unreachable"),
         };
-        match handle_id{
-            AssetLifecycleEvent::Free(id)=>assets.remove(id),
+        match event{
+            AssetLifecycleEvent::Create(id,asset)=>{
+                // the asset was freed while it was still loading
+                if asset_server.load_states.get(&id).is_none(){
+                    continue;
+                }
+                let asset = match asset.downcast::<T>(){
+                    Ok(asset)=>asset,
+                    Err(_)=>unreachable!("lifecycle only receives its own asset type"),
+                };
+                // a reloaded asset replaces the old one, together with whatever was derived from it
+                assets.insert(id,*asset);
+                asset_server.load_states.insert(id,LoadState::Loaded);
+            },
+            AssetLifecycleEvent::Free(id)=>{
+                // a new strong handle showed up after the count dropped to zero
+                if asset_server.asset_ref_counter.ref_counts.contains_key(&id){
+                    continue;
+                }
+                assets.remove(id);
+                asset_server.load_states.remove(&id);
+                asset_server.release_asset(id);
+            },
         };
     }
+    asset_server.evict_over_budget(&mut assets);
 }
 
 
 
-pub fn add_loader(app:&mut App,loader:Box<dyn AssetLoader>){
+pub fn add_loader<L: AssetLoader>(app:&mut App,loader:L){
         let mut asset_server = app.world.get_resource_mut::<AssetServer>().unwrap();
         asset_server.add_loader(loader);
 
 }
+
+pub fn add_saver<S: AssetSaver>(app:&mut App,saver:S){
+        let mut asset_server = app.world.get_resource_mut::<AssetServer>().unwrap();
+        asset_server.add_saver(saver);
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use super::super::test_util::{TextAsset,test_app,asset_server,update_until_loaded};
+    use bevy::prelude::{Events};
+    use bevy::reflect::TypeUuid;
+
+    #[test]
+    fn load_reads_through_memory_asset_io(){
+        let io = MemoryAssetIo::default();
+        io.insert("texts/a.txt","hello");
+        let mut app = test_app(io);
+        let handle = asset_server(&mut app).load::<TextAsset,_>("res://texts/./a.txt");
+        assert_eq!(update_until_loaded(&mut app,handle.id()),LoadState::Loaded);
+        let assets = app.world.get_resource::<Assets<TextAsset>>().unwrap();
+        assert_eq!(assets.get_asset(&handle),Some(&TextAsset("hello".to_string())));
+    }
+
+    #[test]
+    fn missing_file_fails_load(){
+        let mut app = test_app(MemoryAssetIo::default());
+        let handle = asset_server(&mut app).load::<TextAsset,_>("missing.txt");
+        assert_eq!(update_until_loaded(&mut app,handle.id()),LoadState::Failed);
+        let events = app.world.get_resource::<Events<AssetLoadFailed>>().unwrap();
+        let mut reader = events.get_reader();
+        let failed: Vec<_> = reader.iter(events).collect();
+        assert_eq!(failed.len(),1);
+        assert_eq!(failed[0].id,handle.id());
+        assert!(matches!(failed[0].error,AssetLoadError::Io{error:AssetIoError::NotFound(_),..}));
+    }
+
+    /// Holds a handle of `a.txt`, loading it again whenever the test dropped it.
+    #[derive(Default)]
+    struct HeldText(Option<Handle<TextAsset>>);
+
+    fn load_dropped_text_system(mut asset_server: ResMut<AssetServer>,mut held: ResMut<HeldText>){
+        if held.0.is_none(){
+            held.0 = Some(asset_server.load("a.txt"));
+        }
+    }
+
+    #[test]
+    fn loading_again_in_the_frame_the_last_handle_dropped_keeps_asset(){
+        let io = MemoryAssetIo::default();
+        io.insert("a.txt","a");
+        let mut app = test_app(io);
+        app.init_resource::<HeldText>();
+        app.add_system_to_stage(CoreStage::Update,load_dropped_text_system);
+        app.update();
+        let id = app.world.get_resource::<HeldText>().unwrap().0.as_ref().unwrap().id();
+        assert_eq!(update_until_loaded(&mut app,id),LoadState::Loaded);
+
+        // the free is queued in `CoreStage::PreUpdate`, the system loads the path in `CoreStage::Update`
+        app.world.get_resource_mut::<HeldText>().unwrap().0 = None;
+        app.update();
+        app.update();
+        assert_eq!(asset_server(&mut app).get_load_state(id),LoadState::Loaded);
+        assert!(app.world.get_resource::<Assets<TextAsset>>().unwrap().get(&id).is_some());
+    }
+
+    fn text_assets(app: &mut App)->bevy::prelude::Mut<Assets<TextAsset>>{
+        return app.world.get_resource_mut::<Assets<TextAsset>>().unwrap();
+    }
+
+    #[test]
+    fn memory_budget_evicts_only_unheld_assets(){
+        let io = MemoryAssetIo::default();
+        io.insert("a.txt","aaaa");
+        let mut app = test_app(io);
+        let held = asset_server(&mut app).load::<TextAsset,_>("a.txt");
+        assert_eq!(update_until_loaded(&mut app,held.id()),LoadState::Loaded);
+        app.update();
+
+        // the held asset is the least recently accessed one
+        let unheld = HandleId::generated::<TextAsset>();
+        text_assets(&mut app).insert(unheld,TextAsset("bbbb".to_string()));
+        text_assets(&mut app).set_memory_budget(Some(4));
+        app.update();
+        assert!(text_assets(&mut app).get(&held.id()).is_some());
+        assert!(text_assets(&mut app).get(&unheld).is_none());
+    }
+
+    /// Loads `.linked` files as text with an upper case copy labeled `upper`, and declares a
+    /// dependency on `b.txt`.
+    struct LinkedTextLoader;
+
+    impl AssetLoader for LinkedTextLoader{
+        type Asset = TextAsset;
+        type Settings = ();
+
+        fn load(&self,bytes:&[u8],_settings:&(),load_context:&mut LoadContext)->Result<TextAsset,AssetLoadError>{
+            let text = String::from_utf8_lossy(bytes).to_string();
+            load_context.set_labeled_asset("upper",TextAsset(text.to_uppercase()));
+            load_context.add_dependency::<TextAsset,_>("b.txt");
+            return Ok(TextAsset(text));
+        }
+
+        fn extensions(&self)->&[&str]{
+            return &["linked"];
+        }
+    }
+
+    #[test]
+    fn evicted_asset_releases_its_labeled_assets_and_dependencies(){
+        let io = MemoryAssetIo::default();
+        io.insert("a.linked","aaaa");
+        io.insert("b.txt","bbbb");
+        let mut app = test_app(io);
+        add_loader(&mut app,LinkedTextLoader);
+        // loaded without a handle, so nothing holds it and it may be evicted
+        asset_server(&mut app).load_path(AssetPath::from("a.linked"),None);
+        let parent = HandleId::from(AssetPath::from("a.linked"));
+        let child = HandleId::from(AssetPath::from("a.linked#upper"));
+        let dependency = HandleId::from(AssetPath::from("b.txt"));
+        assert_eq!(update_until_loaded(&mut app,parent),LoadState::Loaded);
+        assert_eq!(update_until_loaded(&mut app,dependency),LoadState::Loaded);
+        app.update();
+
+        text_assets(&mut app).set_memory_budget(Some(0));
+        for _ in 0..3{
+            app.update();
+        }
+        for id in [parent,child,dependency]{
+            assert!(text_assets(&mut app).get(&id).is_none());
+            assert_eq!(asset_server(&mut app).get_load_state(id),LoadState::NotLoaded);
+        }
+        assert!(!asset_server(&mut app).children.contains_key(&parent));
+        assert!(!asset_server(&mut app).dependencies.contains_key(&parent));
+    }
+
+    struct ReadText(HandleId);
+
+    fn read_text_system(assets: Res<Assets<TextAsset>>,read: Res<ReadText>){
+        assets.get(&read.0);
+    }
+
+    #[test]
+    fn assets_read_this_frame_are_not_evicted(){
+        let mut app = test_app(MemoryAssetIo::default());
+        let id = HandleId::generated::<TextAsset>();
+        text_assets(&mut app).insert(id,TextAsset("a".to_string()));
+        text_assets(&mut app).set_memory_budget(Some(0));
+        app.insert_resource(ReadText(id));
+        app.add_system_to_stage(CoreStage::Update,read_text_system);
+        for _ in 0..3{
+            app.update();
+        }
+        assert!(text_assets(&mut app).get(&id).is_some());
+    }
+
+    fn load_failures(app: &App)->Vec<(HandleId,String)>{
+        let events = app.world.get_resource::<Events<AssetLoadFailed>>().unwrap();
+        let mut reader = events.get_reader();
+        return reader.iter(events).map(|failed|(failed.id,failed.error.to_string())).collect();
+    }
+
+    #[derive(TypeUuid)]
+    #[uuid = "9b2f6c1e-4a7d-4e35-8c0b-1d5e3f7a9c24"]
+    struct UnregisteredAsset;
+
+    #[test]
+    fn unregistered_asset_type_fails_load(){
+        let io = MemoryAssetIo::default();
+        io.insert("a.txt","a");
+        let mut app = test_app(io);
+        let handle = asset_server(&mut app).load::<UnregisteredAsset,_>("a.txt");
+        assert_eq!(update_until_loaded(&mut app,handle.id()),LoadState::Failed);
+        let failures = load_failures(&app);
+        assert_eq!(failures.len(),1);
+        assert_eq!(failures[0].0,handle.id());
+        assert!(failures[0].1.contains("not registered"));
+    }
+
+    #[test]
+    fn path_leaving_its_root_fails_load(){
+        let io = MemoryAssetIo::default();
+        io.insert("x.txt","x");
+        let mut app = test_app(io);
+        let handle = asset_server(&mut app).load::<TextAsset,_>("user://a/../../x.txt");
+        assert_eq!(asset_server(&mut app).get_load_state(handle.id()),LoadState::Failed);
+        app.update();
+        let failed = load_failures(&app);
+        assert_eq!(failed.len(),1);
+        assert_eq!(failed[0].0,handle.id());
+        assert!(failed[0].1.contains("leaves the root"));
+    }
+
+    #[test]
+    fn colliding_id_fails_load_and_keeps_other_asset(){
+        let io = MemoryAssetIo::default();
+        io.insert("a.txt","a");
+        io.insert("b.txt","b");
+        let mut app = test_app(io);
+        let held = asset_server(&mut app).load::<TextAsset,_>("a.txt");
+        assert_eq!(update_until_loaded(&mut app,held.id()),LoadState::Loaded);
+
+        // pretend another path got the id of `b.txt` first
+        let id = HandleId::from(AssetPath::from("b.txt"));
+        asset_server(&mut app).registered_paths.insert(id,AssetPath::from("other.txt"));
+        let handle = asset_server(&mut app).load::<TextAsset,_>("b.txt");
+        assert_ne!(handle.id(),id);
+        app.update();
+        assert_eq!(asset_server(&mut app).get_load_state(handle.id()),LoadState::Failed);
+        assert_eq!(asset_server(&mut app).get_load_state(id),LoadState::NotLoaded);
+        let failed = load_failures(&app);
+        assert_eq!(failed.len(),1);
+        assert!(failed[0].1.contains("have the same id"));
+        assert!(text_assets(&mut app).get(&held.id()).is_some());
+    }
+
+    #[test]
+    fn paths_stay_registered_after_their_asset_is_freed(){
+        let io = MemoryAssetIo::default();
+        io.insert("a.txt","a");
+        io.insert("b.txt","b");
+        let mut app = test_app(io);
+        let handle = asset_server(&mut app).load::<TextAsset,_>("a.txt");
+        let id = handle.id();
+        assert_eq!(update_until_loaded(&mut app,id),LoadState::Loaded);
+        drop(handle);
+        for _ in 0..3{
+            app.update();
+        }
+        assert!(asset_server(&mut app).get_handle_path(id).is_none());
+        assert_eq!(asset_server(&mut app).registered_paths.get(&id),Some(&AssetPath::from("a.txt")));
+
+        // pretend another path had the id of `b.txt` and was freed since
+        let id = HandleId::from(AssetPath::from("b.txt"));
+        asset_server(&mut app).registered_paths.insert(id,AssetPath::from("other.txt"));
+        let handle = asset_server(&mut app).load::<TextAsset,_>("b.txt");
+        assert_ne!(handle.id(),id);
+        app.update();
+        assert_eq!(asset_server(&mut app).get_load_state(handle.id()),LoadState::Failed);
+        assert!(load_failures(&app).iter().any(|(_,error)|error.contains("have the same id")));
+    }
+}
@@ -1,31 +1,90 @@
 use super::handle::{HandleId,Handle};
+use super::asset_ref_counter::{RefChange};
+use super::asset_event::{AssetEvent};
+use super::asset_memory::{AssetMemory,AssetMemoryUsage};
+use bevy::prelude::{EventWriter,ResMut};
 use std::collections::{HashMap};
+use std::sync::atomic::{AtomicU64,Ordering};
 use bevy::asset::Asset;
+use crossbeam_channel::{Sender};
+
+struct AssetEntry<T>{
+    asset: T,
+    // stamped through `&self` by `get`, storages are shared between parallel systems
+    last_access: AtomicU64,
+}
 
 pub struct Assets<T:Asset>{
-    assets: HashMap<HandleId,T>,
+    assets: HashMap<HandleId,AssetEntry<T>>,
+    pub(crate) ref_change_sender: Sender<RefChange>,
+    events: Vec<AssetEvent<T>>,
+    pub(crate) frame: u64,
+    memory_budget: Option<usize>,
 }
 
 impl<T:Asset> Assets<T>{
-    pub fn new()->Self{
+    pub(crate) fn new(ref_change_sender: Sender<RefChange>)->Self{
         Self{
             assets:Default::default(),
+            ref_change_sender,
+            events: Default::default(),
+            frame: 0,
+            memory_budget: None,
         }
     }
+    /// Sends `AssetEvent::Modified` when an asset is replaced, `AssetEvent::Created` otherwise.
     pub fn insert(&mut self,id: HandleId,asset: T){
-        self.assets.insert(id,asset);
+        let handle = Handle::weak(id);
+        let entry = AssetEntry{
+            asset,
+            last_access: AtomicU64::new(self.frame),
+        };
+        if self.assets.insert(id,entry).is_some(){
+            self.events.push(AssetEvent::Modified{handle});
+        }else{
+            self.events.push(AssetEvent::Created{handle});
+        }
+    }
+
+    /// Adds an asset made at runtime under a freshly generated id. It takes part in ref
+    /// counting like a loaded asset and is freed once the returned handle and its clones drop.
+    pub fn add(&mut self,asset: T)->Handle<T>{
+        let id = HandleId::generated::<T>();
+        self.insert(id,asset);
+        return Handle::strong(id,self.ref_change_sender.clone());
+    }
+
+    /// Replaces the asset behind `handle`, or puts it there if there is none yet.
+    pub fn set<H: Into<HandleId>>(&mut self,handle: H,asset: T)->Handle<T>{
+        let id = handle.into();
+        self.insert(id,asset);
+        return Handle::strong(id,self.ref_change_sender.clone());
     }
 
     pub fn remove(&mut self,id: HandleId){
-        self.assets.remove(&id);
+        if self.assets.remove(&id).is_some(){
+            self.events.push(AssetEvent::Removed{handle:Handle::weak(id)});
+        }
+    }
+
+    /// Like `remove`, but sends `AssetEvent::Evicted`.
+    pub(crate) fn evict(&mut self,id: HandleId){
+        if self.assets.remove(&id).is_some(){
+            self.events.push(AssetEvent::Evicted{handle:Handle::weak(id)});
+        }
     }
 
     pub fn get(&self,id: &HandleId)->Option<&T>{
-        return self.assets.get(id);
+        let entry = self.assets.get(id)?;
+        entry.last_access.store(self.frame,Ordering::Relaxed);
+        return Some(&entry.asset);
     }
 
+    /// Does not send `AssetEvent::Modified`, the renderer mutably borrows assets every frame.
     pub fn get_mut(&mut self,id: &HandleId)->Option<&mut T>{
-        return self.assets.get_mut(id);
+        let entry = self.assets.get_mut(id)?;
+        *entry.last_access.get_mut() = self.frame;
+        return Some(&mut entry.asset);
     }
 
     pub fn get_asset(&self,handle: &Handle<T>)->Option<&T>{
@@ -36,4 +95,55 @@ impl<T:Asset> Assets<T>{
         return self.get_mut(&handle.id);
     }
 
+    /// The `AssetServer::frame` the asset was last read or written through `get`, `get_mut`
+    /// or their handle variants, or inserted.
+    pub fn last_access(&self,id: &HandleId)->Option<u64>{
+        return self.assets.get(id).map(|entry|entry.last_access.load(Ordering::Relaxed));
+    }
+
+    pub fn memory_budget(&self)->Option<usize>{
+        self.memory_budget
+    }
+
+    /// Caps the total bytes of the stored assets. While over it, assets without strong handles
+    /// are evicted, least recently accessed first; assets held by a strong handle are kept even
+    /// above the budget, so their handles never find them gone. An evicted loaded asset comes
+    /// back with the next `AssetServer::load` of its path. Assets accessed during the current
+    /// frame are kept as well.
+    pub fn set_memory_budget(&mut self,budget: Option<usize>){
+        self.memory_budget = budget;
+    }
+
+    pub(crate) fn ids(&self)->impl Iterator<Item=HandleId>+'_{
+        self.assets.keys().copied()
+    }
+
+    pub(crate) fn access_order(&self)->Vec<(u64,HandleId)>{
+        let mut order: Vec<_> = self.assets.iter()
+            .map(|(&id,entry)|(entry.last_access.load(Ordering::Relaxed),id))
+            .collect();
+        order.sort_by_key(|&(last_access,_)|last_access);
+        return order;
+    }
+}
+
+impl<T:Asset+AssetMemoryUsage> Assets<T>{
+    /// Sum of what the stored assets report through `AssetMemoryUsage`.
+    pub fn memory_usage(&self)->AssetMemory{
+        let mut usage = AssetMemory::default();
+        for entry in self.assets.values(){
+            usage += entry.asset.memory_usage();
+        }
+        return usage;
+    }
+
+    pub(crate) fn asset_memory_usage(&self,id: &HandleId)->AssetMemory{
+        return self.assets.get(id).map(|entry|entry.asset.memory_usage()).unwrap_or_default();
+    }
+}
+
+pub(crate) fn asset_event_system<T:Asset>(mut assets: ResMut<Assets<T>>,mut events: EventWriter<AssetEvent<T>>){
+    if !assets.events.is_empty(){
+        events.send_batch(assets.events.drain(..));
+    }
 }
@@ -0,0 +1,63 @@
+//! The cook manifest lists the source assets the asset processor has cooked, and where the
+//! cooked file of each one is. `AssetServer` consults it so games keep loading source paths
+//! such as `textures/wood.png` while the bytes come from `textures/wood.texture`.
+
+use super::io::{AssetIo,AssetIoError};
+use serde::{Deserialize,Serialize};
+use std::collections::{BTreeMap};
+use std::path::Path;
+
+/// Where the asset processor writes the manifest, relative to its output directory.
+pub const COOK_MANIFEST_PATH: &str = "cook_manifest.ron";
+
+#[derive(Clone,Debug,Default,Deserialize,Serialize)]
+pub struct CookManifest{
+    /// Keyed by source path, relative to the asset root and with `/` separators.
+    pub entries: BTreeMap<String,CookedAsset>,
+    /// Files without a cooker, copied to the same path. Keyed like `entries`, with the
+    /// `utils::fnv1a_hash` of the copied bytes.
+    #[serde(default)]
+    pub copied: BTreeMap<String,u64>,
+}
+
+#[derive(Clone,Debug,PartialEq,Eq,Deserialize,Serialize)]
+pub struct CookedAsset{
+    /// Path of the cooked file, relative to the asset root.
+    pub output: String,
+    /// `utils::fnv1a_hash` of the source file the output was cooked from.
+    pub source_hash: u64,
+    /// `utils::fnv1a_hash` of the source's `.meta` file, if it had one.
+    pub meta_hash: Option<u64>,
+    /// `AssetCooker::version` of the cooker that wrote the output.
+    pub cooker_version: u32,
+}
+
+/// Set of possible cook manifest errors.
+#[derive(Debug, thiserror::Error)]
+pub enum CookManifestError{
+    #[error("Failed to read cook manifest: {0}")]
+    Io(#[from] AssetIoError),
+    #[error("Invalid cook manifest: {0}")]
+    Parse(String),
+}
+
+impl CookManifest{
+    pub fn from_bytes(bytes: &[u8])->Result<Self,CookManifestError>{
+        return ron::de::from_bytes(bytes).map_err(|e|CookManifestError::Parse(e.to_string()));
+    }
+
+    /// Reads `COOK_MANIFEST_PATH` through `asset_io`, blocks until it is read.
+    pub fn open(asset_io: &dyn AssetIo)->Result<Self,CookManifestError>{
+        let bytes = asset_io.read(Path::new(COOK_MANIFEST_PATH))?;
+        return Self::from_bytes(&bytes);
+    }
+
+    pub fn to_ron_string(&self)->String{
+        // the manifest only holds strings and integers, serializing it cannot fail
+        return ron::ser::to_string_pretty(self,ron::ser::PrettyConfig::default()).unwrap();
+    }
+
+    pub fn cooked_path(&self,source_path: &str)->Option<&str>{
+        return self.entries.get(source_path).map(|entry|entry.output.as_str());
+    }
+}
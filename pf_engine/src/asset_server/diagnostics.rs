@@ -0,0 +1,108 @@
+use super::asset_server::{AssetServer};
+use super::asset_memory::{AssetMemory,AssetMemoryUsage};
+use super::assets::{Assets};
+use super::handle::{HandleId};
+use super::load_state::{LoadState};
+use bevy::asset::{Asset};
+use bevy::ecs::schedule::SystemLabel;
+use bevy::prelude::{EventReader,Res,ResMut};
+use bevy::utils::Uuid;
+use crate::log::{info,error};
+use serde::{Serialize};
+use std::collections::{HashMap};
+
+/// One asset known to the `AssetServer`, stored or still loading or failed.
+#[derive(Clone, Debug, Serialize)]
+pub struct AssetDiagnostic{
+    #[serde(skip)]
+    pub id: HandleId,
+    /// `None` for assets added at runtime.
+    pub path: Option<String>,
+    /// `None` while a path loaded untyped has not produced its asset yet.
+    pub type_uuid: Option<String>,
+    pub type_name: Option<&'static str>,
+    /// Strong handles, a labeled asset in use adds one to the asset of its file.
+    pub ref_count: usize,
+    pub load_state: LoadState,
+    pub memory: AssetMemory,
+    /// Time from starting the load to the result reaching the game thread, of the latest load.
+    pub load_duration_ms: Option<f64>,
+}
+
+/// Every asset the server knows about, rebuilt during `AssetStage::AssetEvents`.
+#[derive(Default)]
+pub struct AssetDiagnostics{
+    frame: u64,
+    stored: HashMap<Uuid,Vec<AssetDiagnostic>>, // type_uuid to the assets in its `Assets<T>`
+    unstored: Vec<AssetDiagnostic>, // loading or failed
+}
+
+/// Send to log `AssetDiagnostics` as JSON once this frame's diagnostics are collected.
+#[derive(Debug, Default)]
+pub struct DumpAssetDiagnostics;
+
+#[derive(SystemLabel, Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct CollectAssetDiagnostics;
+
+impl AssetDiagnostics{
+    /// The `AssetServer::frame` the diagnostics were collected in.
+    pub fn frame(&self)->u64{
+        self.frame
+    }
+
+    pub fn assets(&self)->impl Iterator<Item=&AssetDiagnostic>{
+        self.stored.values().flatten().chain(self.unstored.iter())
+    }
+
+    pub fn get(&self,id: HandleId)->Option<&AssetDiagnostic>{
+        self.assets().find(|asset|asset.id == id)
+    }
+
+    pub fn memory_usage(&self)->AssetMemory{
+        let mut usage = AssetMemory::default();
+        for asset in self.assets(){
+            usage += asset.memory;
+        }
+        return usage;
+    }
+
+    /// A JSON array of the assets, sorted by path.
+    pub fn to_json(&self)->Result<String,serde_json::Error>{
+        let mut assets: Vec<_> = self.assets().collect();
+        assets.sort_by(|a,b|a.path.cmp(&b.path));
+        return serde_json::to_string_pretty(&assets);
+    }
+}
+
+pub(crate) fn asset_diagnostics_system<T:Asset+AssetMemoryUsage>(
+    asset_server: Res<AssetServer>,
+    assets: Res<Assets<T>>,
+    mut diagnostics: ResMut<AssetDiagnostics>,
+    ){
+    let stored = assets.ids()
+        .map(|id|asset_server.asset_diagnostic(id,Some(T::TYPE_UUID),assets.asset_memory_usage(&id)))
+        .collect();
+    diagnostics.stored.insert(T::TYPE_UUID,stored);
+}
+
+pub(crate) fn asset_server_diagnostics_system(
+    asset_server: Res<AssetServer>,
+    mut diagnostics: ResMut<AssetDiagnostics>,
+    ){
+    diagnostics.frame = asset_server.frame();
+    diagnostics.unstored = asset_server.unstored_asset_diagnostics();
+}
+
+pub(crate) fn dump_asset_diagnostics_system(
+    diagnostics: Res<AssetDiagnostics>,
+    mut dump_events: EventReader<DumpAssetDiagnostics>,
+    ){
+    // several requests in one frame log once
+    if dump_events.iter().count() == 0{
+        return;
+    }
+    match diagnostics.to_json(){
+        Ok(json)=>info!("asset diagnostics of frame {}:\n{}",diagnostics.frame,json),
+        Err(e)=>error!("failed to serialize asset diagnostics: {}",e),
+    }
+}
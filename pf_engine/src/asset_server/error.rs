@@ -0,0 +1,131 @@
+//! Contains all possible errors that may occur while loading an asset.
+
+use super::asset_path::{AssetPath};
+use super::io::{AssetIoError};
+use bevy::utils::Uuid;
+
+/// Set of possible asset loading errors.
+#[derive(Debug, thiserror::Error)]
+pub enum AssetLoadError {
+    /// The path has no extension, so no loader can be picked for it.
+    #[error("Asset path {0:?} has no extension")]
+    MissingExtension(AssetPath),
+    /// None of the registered loaders claims the extension.
+    #[error("No loader is registered for extension \"{extension}\" of {path:?}")]
+    NoLoaderForExtension {
+        /// Path that was requested.
+        path: AssetPath,
+        /// Extension nobody claims.
+        extension: String,
+    },
+    /// Several loaders claim the extension, or accept the content when sniffing it.
+    #[error("Several loaders can load {path:?}: {loaders:?}")]
+    AmbiguousLoader {
+        /// Path that was requested.
+        path: AssetPath,
+        /// Type names of the loaders.
+        loaders: Vec<&'static str>,
+    },
+    /// The file loaded, but its loader emitted no sub-asset with the requested label.
+    #[error("{path:?} has no labeled asset \"{label}\"")]
+    MissingLabel {
+        /// Path of the file.
+        path: AssetPath,
+        /// Label that was requested.
+        label: String,
+    },
+    /// The loader produced an asset type that was never registered with `register_asset`.
+    #[error("Loader of {path:?} produces {type_name}, which is not a registered asset type")]
+    UnregisteredAssetType {
+        /// Path that was requested.
+        path: AssetPath,
+        /// Type name of the produced asset.
+        type_name: &'static str,
+    },
+    /// The file could not be read.
+    #[error("Failed to read {path:?}: {error}")]
+    Io {
+        /// Path that was requested.
+        path: AssetPath,
+        /// Underlying asset io error.
+        error: AssetIoError,
+    },
+    /// The loader could not make sense of the file content.
+    #[error("Failed to decode {path:?}: {message}")]
+    Decode {
+        /// Path that was requested.
+        path: AssetPath,
+        /// Decoder error message.
+        message: String,
+    },
+    /// The `.meta` file next to the asset does not match the loader's settings.
+    #[error("Invalid meta file of {path:?}: {message}")]
+    InvalidMeta {
+        /// Path that was requested.
+        path: AssetPath,
+        /// Deserializer error message.
+        message: String,
+    },
+    /// Another path the server saw has the same `AssetPathId`.
+    #[error("Asset paths {path:?} and {other:?} have the same id, rename one of them")]
+    IdCollision {
+        /// Path that was requested.
+        path: AssetPath,
+        /// Path that got the id first.
+        other: AssetPath,
+    },
+    /// The path climbs above the root of its scheme, e.g. `user://../x`.
+    #[error("Asset path {0:?} leaves the root of its scheme")]
+    PathOutsideRoot(AssetPath),
+    /// No loader that claims the path produces the asset type the handle asks for.
+    #[error("No loader of {path:?} produces {expected}")]
+    TypeMismatch {
+        /// Path that was requested.
+        path: AssetPath,
+        /// Type name of the requested asset.
+        expected: &'static str,
+    },
+    /// The handle asks for an asset type that was never registered with `register_asset`.
+    #[error("{path:?} is requested as asset type {type_uuid}, which is not registered")]
+    UnregisteredRequestedType {
+        /// Path that was requested.
+        path: AssetPath,
+        /// Type uuid of the requested asset.
+        type_uuid: Uuid,
+    },
+}
+
+/// Set of possible asset saving errors.
+#[derive(Debug, thiserror::Error)]
+pub enum AssetSaveError {
+    /// No saver of the asset type claims an extension of the path.
+    #[error("No saver of {type_name} is registered for {path:?}")]
+    NoSaver {
+        /// Path to save to.
+        path: AssetPath,
+        /// Type name of the asset.
+        type_name: &'static str,
+    },
+    /// The path climbs above the root of its scheme, e.g. `user://../x`.
+    #[error("Asset path {0:?} leaves the root of its scheme")]
+    PathOutsideRoot(AssetPath),
+    /// Nothing is stored behind the handle.
+    #[error("No asset to save to {0:?}")]
+    MissingAsset(AssetPath),
+    /// The saver could not encode the asset.
+    #[error("Failed to encode {path:?}: {message}")]
+    Encode {
+        /// Path to save to.
+        path: AssetPath,
+        /// Encoder error message.
+        message: String,
+    },
+    /// The file could not be written.
+    #[error("Failed to write {path:?}: {error}")]
+    Io {
+        /// Path to save to.
+        path: AssetPath,
+        /// Underlying asset io error.
+        error: AssetIoError,
+    },
+}
@@ -1,8 +1,14 @@
 use std::marker::PhantomData;
 use super::asset_path::{AssetPathId,AssetPath};
-use std::sync::mpsc::{ SyncSender};
+use crossbeam_channel::{Sender};
 use super::asset_ref_counter::{RefChange};
+use super::asset_server::{AssetServer};
+use bevy::asset::{Asset};
+use bevy::utils::Uuid;
+use std::sync::atomic::{AtomicU64,Ordering};
 
+/// A strong handle keeps its asset alive, the asset is freed one frame after the last
+/// strong handle is dropped. A weak handle only refers to the asset.
 #[derive(Debug)]
 pub struct Handle<T>{
     pub(crate)id: HandleId,
@@ -20,17 +26,59 @@ impl<T> Clone for Handle<T>{
 
 }
 
+impl<T> Drop for Handle<T>{
+    fn drop(&mut self){
+        if let HandleType::Strong(ref sender) = self.handle_type{
+            // a send error only means the asset server is already gone
+            let _ = sender.send(RefChange::Decrement(self.id));
+        }
+    }
+}
+
 impl<T> Handle<T>{
-    fn strong(id: HandleId,sender: SyncSender<RefChange>)->Self{
+    pub(crate) fn strong(id: HandleId,sender: Sender<RefChange>)->Self{
         HandleUntyped::strong(id,sender).typed()
     }
-    fn weak(id: HandleId)->Self{
+
+    pub fn weak(id: HandleId)->Self{
         Self {
             id,
             handle_type: HandleType::Weak,
             marker:PhantomData,
         }
     }
+
+    pub fn id(&self)->HandleId{
+        self.id
+    }
+
+    pub fn is_weak(&self)->bool{
+        matches!(self.handle_type,HandleType::Weak)
+    }
+
+    pub fn is_strong(&self)->bool{
+        matches!(self.handle_type,HandleType::Strong(_))
+    }
+
+    pub fn clone_weak(&self)->Self{
+        Handle::weak(self.id)
+    }
+
+    pub fn clone_untyped(&self)->HandleUntyped{
+        match self.handle_type{
+            HandleType::Weak=>HandleUntyped::weak(self.id),
+            HandleType::Strong(ref sender)=>HandleUntyped::strong(self.id,sender.clone()),
+        }
+    }
+
+    /// Turns a weak handle into a strong one, does nothing if it is strong already. The
+    /// reference is counted right away, like the one of a handle returned by `load`.
+    pub fn make_strong(&mut self,asset_server: &mut AssetServer){
+        if self.is_strong(){
+            return;
+        }
+        *self = asset_server.strong_handle(self.id).typed();
+    }
 }
 
 
@@ -44,34 +92,129 @@ impl<T> Handle<T>{
     Ord,
     PartialOrd,
 )]
-pub struct HandleId (pub AssetPathId);
+pub enum HandleId {
+    /// Minted at runtime for an asset that does not come from a file, see `HandleId::generated`.
+    Id(Uuid,u64),
+    /// Derived from the path the asset is loaded from.
+    AssetPathId(AssetPathId),
+}
+
+static NEXT_GENERATED_ID: AtomicU64 = AtomicU64::new(0);
+
+impl HandleId{
+    /// A unique id in the namespace of `T`'s type uuid.
+    pub fn generated<T: Asset>()->Self{
+        Self::generated_with_type_uuid(T::TYPE_UUID)
+    }
+
+    pub(crate) fn generated_with_type_uuid(type_uuid: Uuid)->Self{
+        HandleId::Id(type_uuid,NEXT_GENERATED_ID.fetch_add(1,Ordering::Relaxed))
+    }
+}
 
 impl From<AssetPath> for HandleId{
     fn from(asset_path: AssetPath)->Self{
-        Self(asset_path.get_id())
+        HandleId::AssetPathId(asset_path.get_id())
     }
 }
 
+impl<T> From<&Handle<T>> for HandleId{
+    fn from(handle: &Handle<T>)->Self{
+        handle.id
+    }
+}
+
+impl From<&HandleUntyped> for HandleId{
+    fn from(handle: &HandleUntyped)->Self{
+        handle.id
+    }
+}
+
+#[derive(Debug)]
 pub struct HandleUntyped{
-    id: HandleId,
+    pub(crate)id: HandleId,
     handle_type: HandleType
 }
 
+impl Clone for HandleUntyped{
+    fn clone(&self)->Self{
+        match self.handle_type{
+            HandleType::Weak=>HandleUntyped::weak(self.id),
+            HandleType::Strong(ref sender)=>HandleUntyped::strong(self.id,sender.clone()),
+        }
+    }
+}
+
+impl Drop for HandleUntyped{
+    fn drop(&mut self){
+        if let HandleType::Strong(ref sender) = self.handle_type{
+            // a send error only means the asset server is already gone
+            let _ = sender.send(RefChange::Decrement(self.id));
+        }
+    }
+}
+
 impl HandleUntyped{
-    pub(crate) fn strong(handle_id:HandleId,sender: SyncSender<RefChange>)->Self{
-        sender.send(RefChange::Increment(handle_id)).unwrap();
+    pub(crate) fn strong(handle_id:HandleId,sender: Sender<RefChange>)->Self{
+        // a send error only means the asset server is already gone
+        let _ = sender.send(RefChange::Increment(handle_id));
         Self{
             id:handle_id,
             handle_type:HandleType::Strong(sender),
         }
     }
 
+    /// For a reference the asset server counted already, sends no `RefChange::Increment`.
+    pub(crate) fn counted(handle_id:HandleId,sender: Sender<RefChange>)->Self{
+        Self{
+            id:handle_id,
+            handle_type:HandleType::Strong(sender),
+        }
+    }
+
+    pub fn weak(handle_id: HandleId)->Self{
+        Self{
+            id:handle_id,
+            handle_type:HandleType::Weak,
+        }
+    }
+
+    pub fn id(&self)->HandleId{
+        self.id
+    }
+
+    pub fn is_weak(&self)->bool{
+        matches!(self.handle_type,HandleType::Weak)
+    }
+
+    pub fn is_strong(&self)->bool{
+        matches!(self.handle_type,HandleType::Strong(_))
+    }
+
+    pub fn clone_weak(&self)->Self{
+        HandleUntyped::weak(self.id)
+    }
+
+    /// Turns a weak handle into a strong one, does nothing if it is strong already. The
+    /// reference is counted right away, like the one of a handle returned by `load`.
+    pub fn make_strong(&mut self,asset_server: &mut AssetServer){
+        if self.is_strong(){
+            return;
+        }
+        *self = asset_server.strong_handle(self.id);
+    }
+
+    pub fn typed_weak<T>(&self)->Handle<T>{
+        Handle::weak(self.id)
+    }
+
     pub fn typed<T>(mut self)->Handle<T>{
         let handle_type = match &self.handle_type{
             HandleType::Weak => HandleType::Weak,
             HandleType::Strong(sender)=> HandleType::Strong(sender.clone()),
         };
 
+        // the count moves to the typed handle, dropping `self` must not decrement it
         self.handle_type = HandleType::Weak;
 
         Handle{
@@ -86,5 +229,82 @@ impl HandleUntyped{
 #[derive(Debug)]
 pub enum HandleType{
     Weak,
-    Strong(SyncSender<RefChange>)
+    Strong(Sender<RefChange>)
+}
+
+#[cfg(test)]
+mod tests{
+    use super::super::assets::{Assets};
+    use super::super::io::{MemoryAssetIo};
+    use super::super::load_state::{LoadState};
+    use super::super::test_util::{TextAsset,test_app,asset_server,update_until_loaded};
+
+    fn stored(app: &bevy::prelude::App,id: super::HandleId)->bool{
+        app.world.get_resource::<Assets<TextAsset>>().unwrap().get(&id).is_some()
+    }
+
+    #[test]
+    fn dropping_last_strong_handle_frees_asset_next_frame(){
+        let io = MemoryAssetIo::default();
+        io.insert("a.txt","a");
+        let mut app = test_app(io);
+        let handle = asset_server(&mut app).load::<TextAsset,_>("a.txt");
+        let id = handle.id();
+        assert_eq!(update_until_loaded(&mut app,id),LoadState::Loaded);
+        let clone = handle.clone();
+        drop(handle);
+        app.update();
+        assert!(stored(&app,id));
+
+        drop(clone);
+        assert!(stored(&app,id));
+        app.update();
+        assert!(!stored(&app,id));
+        assert_eq!(asset_server(&mut app).get_load_state(id),LoadState::NotLoaded);
+    }
+
+    #[test]
+    fn weak_handle_does_not_keep_asset_alive(){
+        let io = MemoryAssetIo::default();
+        io.insert("a.txt","a");
+        let mut app = test_app(io);
+        let handle = asset_server(&mut app).load::<TextAsset,_>("a.txt");
+        assert_eq!(update_until_loaded(&mut app,handle.id()),LoadState::Loaded);
+        let weak = handle.clone_weak();
+        assert!(weak.is_weak());
+        drop(handle);
+        app.update();
+        assert!(!stored(&app,weak.id()));
+    }
+
+    #[test]
+    fn make_strong_keeps_asset_alive(){
+        let io = MemoryAssetIo::default();
+        io.insert("a.txt","a");
+        let mut app = test_app(io);
+        let handle = asset_server(&mut app).load::<TextAsset,_>("a.txt");
+        assert_eq!(update_until_loaded(&mut app,handle.id()),LoadState::Loaded);
+        let mut weak = handle.clone_weak();
+        weak.make_strong(&mut asset_server(&mut app));
+        assert!(weak.is_strong());
+        drop(handle);
+        app.update();
+        app.update();
+        assert!(stored(&app,weak.id()));
+
+        let id = weak.id();
+        drop(weak);
+        app.update();
+        assert!(!stored(&app,id));
+    }
+
+    #[test]
+    fn cloning_handle_after_server_dropped_does_not_panic(){
+        let mut app = test_app(MemoryAssetIo::default());
+        let handle = app.world.get_resource_mut::<Assets<TextAsset>>().unwrap().add(TextAsset("a".to_string()));
+        drop(app);
+        let clone = handle.clone();
+        drop(handle);
+        drop(clone.clone_untyped());
+    }
 }
@@ -0,0 +1,51 @@
+use super::{AssetIo,AssetIoError};
+use crate::platform::android::GAME_LOOPER;
+use ndk::asset::AssetManager;
+use std::ffi::CString;
+use std::io;
+use std::path::{Path,PathBuf};
+
+/// Reads assets packaged in the APK through the activity's `AAssetManager`.
+#[derive(Default)]
+pub struct AndroidAssetIo{}
+
+impl AndroidAssetIo{
+    fn asset_manager(&self)->AssetManager{
+        unsafe{ GAME_LOOPER.as_ref().unwrap().native_activity.asset_manager() }
+    }
+}
+
+pub(super) fn internal_data_path()->PathBuf{
+    unsafe{ GAME_LOOPER.as_ref().unwrap().native_activity.internal_data_path().to_path_buf() }
+}
+
+fn to_c_path(path: &Path)->Result<CString,AssetIoError>{
+    let path = path.to_str().ok_or_else(||{
+        AssetIoError::Io(io::Error::new(io::ErrorKind::InvalidInput,format!("{:?} is not valid utf-8",path)))
+    })?;
+    return CString::new(path).map_err(|e|AssetIoError::Io(io::Error::new(io::ErrorKind::InvalidInput,e)));
+}
+
+impl AssetIo for AndroidAssetIo{
+    fn read(&self,path: &Path)->Result<Vec<u8>,AssetIoError>{
+        let mut asset = self.asset_manager()
+            .open(&to_c_path(path)?)
+            .ok_or_else(||AssetIoError::NotFound(path.to_path_buf()))?;
+        return Ok(asset.get_buffer()?.to_vec());
+    }
+
+    fn exists(&self,path: &Path)->bool{
+        match to_c_path(path){
+            Ok(c_path)=>self.asset_manager().open(&c_path).is_some(),
+            Err(_)=>false,
+        }
+    }
+
+    /// `AAssetDir` only lists files, sub directories are skipped.
+    fn read_dir(&self,path: &Path)->Result<Vec<PathBuf>,AssetIoError>{
+        let dir = self.asset_manager()
+            .open_dir(&to_c_path(path)?)
+            .ok_or_else(||AssetIoError::NotFound(path.to_path_buf()))?;
+        return Ok(dir.map(|name|path.join(name.to_string_lossy().as_ref())).collect());
+    }
+}
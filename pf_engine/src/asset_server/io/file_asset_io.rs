@@ -0,0 +1,103 @@
+use super::{AssetIo,AssetIoError,AssetReader};
+#[cfg(not(target_os="android"))]
+use super::filesystem_watcher::{FilesystemWatcher};
+use std::path::{Path,PathBuf};
+use std::io;
+#[cfg(not(target_os="android"))]
+use std::sync::{RwLock};
+
+/// Reads assets from a folder of the native file system.
+pub struct FileAssetIo{
+    root_path: PathBuf,
+    #[cfg(not(target_os="android"))]
+    filesystem_watcher: RwLock<Option<FilesystemWatcher>>,
+}
+
+impl FileAssetIo{
+    /// `root_path` is resolved against the working directory when it is relative.
+    pub fn new<P: AsRef<Path>>(root_path: P)->Self{
+        Self{
+            root_path: root_path.as_ref().to_path_buf(),
+            #[cfg(not(target_os="android"))]
+            filesystem_watcher: Default::default(),
+        }
+    }
+
+    pub fn root_path(&self)->&Path{
+        &self.root_path
+    }
+}
+
+impl AssetIo for FileAssetIo{
+    fn read(&self,path: &Path)->Result<Vec<u8>,AssetIoError>{
+        let full_path = self.root_path.join(path);
+        return std::fs::read(&full_path).map_err(|e|match e.kind(){
+            io::ErrorKind::NotFound=>AssetIoError::NotFound(full_path),
+            _=>AssetIoError::Io(e),
+        });
+    }
+
+    fn exists(&self,path: &Path)->bool{
+        return self.root_path.join(path).exists();
+    }
+
+    /// Reads from the file as it is asked for, nothing is held in memory.
+    fn open(&self,path: &Path)->Result<Box<dyn AssetReader>,AssetIoError>{
+        let full_path = self.root_path.join(path);
+        return match std::fs::File::open(&full_path){
+            Ok(file)=>Ok(Box::new(file)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound=>Err(AssetIoError::NotFound(full_path)),
+            Err(e)=>Err(AssetIoError::Io(e)),
+        };
+    }
+
+    fn read_dir(&self,path: &Path)->Result<Vec<PathBuf>,AssetIoError>{
+        let full_path = self.root_path.join(path);
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(&full_path)?{
+            entries.push(path.join(entry?.file_name()));
+        }
+        return Ok(entries);
+    }
+
+    fn write(&self,path: &Path,bytes: &[u8])->Result<(),AssetIoError>{
+        let full_path = self.root_path.join(path);
+        if let Some(parent) = full_path.parent(){
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&full_path,bytes)?;
+        return Ok(());
+    }
+
+    #[cfg(not(target_os="android"))]
+    fn watch_for_changes(&self)->Result<(),AssetIoError>{
+        let mut filesystem_watcher = self.filesystem_watcher.write().unwrap();
+        if filesystem_watcher.is_some(){
+            return Ok(());
+        }
+        let mut watcher = FilesystemWatcher::new().map_err(|e|AssetIoError::Watch(e.to_string()))?;
+        watcher.watch(&self.root_path).map_err(|e|AssetIoError::Watch(e.to_string()))?;
+        *filesystem_watcher = Some(watcher);
+        return Ok(());
+    }
+
+    #[cfg(not(target_os="android"))]
+    fn changed_paths(&self)->Vec<PathBuf>{
+        let filesystem_watcher = self.filesystem_watcher.read().unwrap();
+        let changed_paths = match filesystem_watcher.as_ref(){
+            Some(watcher)=>watcher.changed_paths(),
+            None=>return Vec::new(),
+        };
+        if changed_paths.is_empty(){
+            return Vec::new();
+        }
+        // the watcher reports absolute paths
+        let root_path = match self.root_path.canonicalize(){
+            Ok(root_path)=>root_path,
+            Err(_)=>return Vec::new(),
+        };
+        return changed_paths.into_iter()
+            .filter_map(|path|path.strip_prefix(&root_path).ok().map(|path|path.to_path_buf()))
+            .collect();
+    }
+}
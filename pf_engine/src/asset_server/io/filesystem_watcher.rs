@@ -0,0 +1,43 @@
+use crossbeam_channel::{Receiver,unbounded};
+use notify::{Event,EventKind,RecommendedWatcher,RecursiveMode,Watcher};
+use std::collections::{HashSet};
+use std::path::{Path,PathBuf};
+
+/// Collects the files modified under the watched directories, desktop only.
+pub struct FilesystemWatcher{
+    watcher: RecommendedWatcher,
+    receiver: Receiver<notify::Result<Event>>,
+}
+
+impl FilesystemWatcher{
+    pub fn new()->notify::Result<Self>{
+        let (sender,receiver) = unbounded();
+        let watcher = notify::recommended_watcher(move |event|{
+            // the watcher outlives the receiver only while the server is being dropped
+            let _ = sender.send(event);
+        })?;
+        Ok(Self{
+            watcher,
+            receiver,
+        })
+    }
+
+    pub fn watch<P: AsRef<Path>>(&mut self,path: P)->notify::Result<()>{
+        self.watcher.watch(path.as_ref(),RecursiveMode::Recursive)
+    }
+
+    /// Drains pending events, an editor usually emits several of them for one save.
+    pub fn changed_paths(&self)->HashSet<PathBuf>{
+        let mut paths = HashSet::new();
+        for event in self.receiver.try_iter(){
+            let event = match event{
+                Ok(event)=>event,
+                Err(_)=>continue,
+            };
+            if let EventKind::Modify(_) | EventKind::Create(_) = event.kind{
+                paths.extend(event.paths);
+            }
+        }
+        return paths;
+    }
+}
@@ -0,0 +1,71 @@
+use super::{AssetIo,AssetIoError};
+use std::collections::{BTreeSet,HashMap};
+use std::path::{Path,PathBuf};
+use std::sync::{Arc,Mutex,RwLock};
+
+/// Keeps assets in memory, mostly for tests. Clones share the same files, so a clone kept
+/// aside can feed the server after it took ownership of the io.
+#[derive(Clone, Default)]
+pub struct MemoryAssetIo{
+    files: Arc<RwLock<HashMap<PathBuf,Vec<u8>>>>,
+    // `Some` once watching, holds the paths inserted since the last `changed_paths`
+    changed_paths: Arc<Mutex<Option<Vec<PathBuf>>>>,
+}
+
+impl MemoryAssetIo{
+    /// Adds or replaces a file, a replaced file is reported as changed when watching.
+    pub fn insert<P: AsRef<Path>,B: Into<Vec<u8>>>(&self,path: P,bytes: B){
+        let path = path.as_ref().to_path_buf();
+        if let Some(changed_paths) = self.changed_paths.lock().unwrap().as_mut(){
+            changed_paths.push(path.clone());
+        }
+        self.files.write().unwrap().insert(path,bytes.into());
+    }
+
+    pub fn remove<P: AsRef<Path>>(&self,path: P)->Option<Vec<u8>>{
+        return self.files.write().unwrap().remove(path.as_ref());
+    }
+}
+
+impl AssetIo for MemoryAssetIo{
+    fn read(&self,path: &Path)->Result<Vec<u8>,AssetIoError>{
+        return self.files.read().unwrap()
+            .get(path)
+            .cloned()
+            .ok_or_else(||AssetIoError::NotFound(path.to_path_buf()));
+    }
+
+    fn exists(&self,path: &Path)->bool{
+        return self.files.read().unwrap().keys().any(|file|file.starts_with(path));
+    }
+
+    fn read_dir(&self,path: &Path)->Result<Vec<PathBuf>,AssetIoError>{
+        let files = self.files.read().unwrap();
+        let entries: BTreeSet<PathBuf> = files.keys()
+            .filter_map(|file|file.strip_prefix(path).ok())
+            .filter_map(|relative|relative.components().next())
+            .map(|component|path.join(component))
+            .collect();
+        if entries.is_empty(){
+            return Err(AssetIoError::NotFound(path.to_path_buf()));
+        }
+        return Ok(entries.into_iter().collect());
+    }
+
+    fn write(&self,path: &Path,bytes: &[u8])->Result<(),AssetIoError>{
+        self.insert(path,bytes);
+        return Ok(());
+    }
+
+    fn watch_for_changes(&self)->Result<(),AssetIoError>{
+        self.changed_paths.lock().unwrap().get_or_insert_with(Vec::new);
+        return Ok(());
+    }
+
+    fn changed_paths(&self)->Vec<PathBuf>{
+        return self.changed_paths.lock().unwrap()
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default();
+    }
+}
@@ -0,0 +1,87 @@
+//! Sources the asset server reads bytes from.
+
+mod file_asset_io;
+mod memory_asset_io;
+mod virtual_asset_io;
+#[cfg(target_os="android")]
+mod android_asset_io;
+#[cfg(not(target_os="android"))]
+pub mod filesystem_watcher;
+
+pub use file_asset_io::{FileAssetIo};
+pub use memory_asset_io::{MemoryAssetIo};
+pub use virtual_asset_io::{VirtualAssetIo};
+#[cfg(target_os="android")]
+pub use android_asset_io::{AndroidAssetIo};
+
+use std::path::{Path,PathBuf};
+use std::io::{self,Read,Seek};
+
+/// Set of possible asset io errors.
+#[derive(Debug, thiserror::Error)]
+pub enum AssetIoError {
+    /// Nothing exists at the path.
+    #[error("Path not found: {0:?}")]
+    NotFound(PathBuf),
+    /// Any other error of the underlying storage.
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    /// The file system watcher could not be set up.
+    #[error("Failed to watch for changes: {0}")]
+    Watch(String),
+    /// The io does not support the operation.
+    #[error("Asset io does not support {0}")]
+    Unsupported(&'static str),
+}
+
+/// Storage the asset server reads asset bytes from. Paths are relative to the root of the storage.
+pub trait AssetIo: Send + Sync + 'static{
+    fn read(&self,path: &Path)->Result<Vec<u8>,AssetIoError>;
+    fn exists(&self,path: &Path)->bool;
+    /// Lists the entries directly below `path`, each joined onto `path`.
+    fn read_dir(&self,path: &Path)->Result<Vec<PathBuf>,AssetIoError>;
+
+    /// Creates or replaces the file at `path`, together with missing parent directories.
+    fn write(&self,_path: &Path,_bytes: &[u8])->Result<(),AssetIoError>{
+        return Err(AssetIoError::Unsupported("writing"));
+    }
+
+    /// Starts collecting modified paths, see `changed_paths`.
+    fn watch_for_changes(&self)->Result<(),AssetIoError>{
+        return Err(AssetIoError::Unsupported("watching for changes"));
+    }
+
+    /// Drains the paths modified since the last call.
+    fn changed_paths(&self)->Vec<PathBuf>{
+        return Vec::new();
+    }
+
+    /// Opens the file at `path` to read parts of it, such as the entries of an asset pack.
+    /// The default reads the whole file into memory.
+    fn open(&self,path: &Path)->Result<Box<dyn AssetReader>,AssetIoError>{
+        return Ok(Box::new(io::Cursor::new(self.read(path)?)));
+    }
+}
+
+/// A file opened by `AssetIo::open`.
+pub trait AssetReader: Read + Seek + Send{}
+
+impl<R: Read + Seek + Send> AssetReader for R{}
+
+/// The io `AssetServer::default` reads from: the APK assets on Android, the `assets` folder
+/// below the working directory elsewhere.
+pub fn default_asset_io()->Box<dyn AssetIo>{
+    #[cfg(target_os="android")]
+    return Box::new(AndroidAssetIo::default());
+    #[cfg(not(target_os="android"))]
+    return Box::new(FileAssetIo::new("assets"));
+}
+
+/// The io `AssetServer::default` mounts at `user://`: the app's internal data directory on
+/// Android, the `user` folder below the working directory elsewhere.
+pub fn default_user_asset_io()->Box<dyn AssetIo>{
+    #[cfg(target_os="android")]
+    return Box::new(FileAssetIo::new(android_asset_io::internal_data_path()));
+    #[cfg(not(target_os="android"))]
+    return Box::new(FileAssetIo::new("user"));
+}
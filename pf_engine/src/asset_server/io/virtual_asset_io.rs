@@ -0,0 +1,212 @@
+use super::{AssetIo,AssetIoError,AssetReader,MemoryAssetIo};
+use crate::asset_server::asset_path::{split_scheme,RES_SCHEME,MEM_SCHEME};
+use std::collections::{BTreeSet};
+use std::path::{Path,PathBuf};
+use std::sync::{Arc,RwLock};
+
+struct Mount{
+    scheme: String,
+    prefix: PathBuf,
+    priority: i32,
+    io: Arc<dyn AssetIo>,
+}
+
+/// Serves `scheme://path` from the ios mounted for the scheme, paths without a scheme are
+/// `res://` ones. Several ios may be mounted at the same scheme and prefix: the one with the
+/// highest priority that has a file wins, among equal priorities the latest mounted. This
+/// lets patches and mods override base files.
+pub struct VirtualAssetIo{
+    // highest priority first, later mounts in front of earlier ones of the same priority
+    mounts: RwLock<Vec<Mount>>,
+    memory: MemoryAssetIo,
+}
+
+impl VirtualAssetIo{
+    /// Mounts `res_io` at `res://` and an empty `MemoryAssetIo` at `mem://`, both with priority 0.
+    pub fn new(res_io: Box<dyn AssetIo>)->Self{
+        let memory = MemoryAssetIo::default();
+        let virtual_io = Self{
+            mounts: Default::default(),
+            memory: memory.clone(),
+        };
+        virtual_io.mount(RES_SCHEME,"",Arc::from(res_io),0);
+        virtual_io.mount(MEM_SCHEME,"",Arc::new(memory),0);
+        return virtual_io;
+    }
+
+    /// The io mounted at `mem://` by `new`.
+    pub fn memory(&self)->&MemoryAssetIo{
+        &self.memory
+    }
+
+    /// Serves `scheme://<prefix>/<path>` from `<path>` of `io`.
+    pub fn mount<P: Into<PathBuf>>(&self,scheme: &str,prefix: P,io: Arc<dyn AssetIo>,priority: i32){
+        let mut mounts = self.mounts.write().unwrap();
+        let index = mounts.iter()
+            .position(|mount|mount.priority <= priority)
+            .unwrap_or(mounts.len());
+        mounts.insert(index,Mount{
+            scheme: scheme.to_string(),
+            prefix: prefix.into(),
+            priority,
+            io,
+        });
+    }
+
+    /// Removes every io mounted at `scheme://<prefix>`, returns whether there was one.
+    pub fn unmount<P: AsRef<Path>>(&self,scheme: &str,prefix: P)->bool{
+        let mut mounts = self.mounts.write().unwrap();
+        let count = mounts.len();
+        mounts.retain(|mount|mount.scheme != scheme || mount.prefix != prefix.as_ref());
+        return mounts.len() != count;
+    }
+
+    /// The mounted ios that may have `path`, in the order they are asked, each with the path
+    /// relative to its mount point.
+    fn resolve(&self,path: &Path)->Vec<(Arc<dyn AssetIo>,PathBuf)>{
+        let path = path.to_string_lossy().replace('\\',"/");
+        let (scheme,rest) = split_scheme(&path);
+        let rest = Path::new(rest);
+        return self.mounts.read().unwrap()
+            .iter()
+            .filter(|mount|mount.scheme == scheme)
+            .filter_map(|mount|{
+                let relative = rest.strip_prefix(&mount.prefix).ok()?;
+                Some((mount.io.clone(),relative.to_path_buf()))
+            })
+            .collect();
+    }
+}
+
+impl AssetIo for VirtualAssetIo{
+    fn read(&self,path: &Path)->Result<Vec<u8>,AssetIoError>{
+        for (io,relative) in self.resolve(path){
+            match io.read(&relative){
+                Err(AssetIoError::NotFound(_))=>continue,
+                result=>return result,
+            }
+        }
+        return Err(AssetIoError::NotFound(path.to_path_buf()));
+    }
+
+    fn exists(&self,path: &Path)->bool{
+        return self.resolve(path).iter().any(|(io,relative)|io.exists(relative));
+    }
+
+    fn open(&self,path: &Path)->Result<Box<dyn AssetReader>,AssetIoError>{
+        for (io,relative) in self.resolve(path){
+            match io.open(&relative){
+                Err(AssetIoError::NotFound(_))=>continue,
+                result=>return result,
+            }
+        }
+        return Err(AssetIoError::NotFound(path.to_path_buf()));
+    }
+
+    /// Merges the entries of every io mounted for `path`.
+    fn read_dir(&self,path: &Path)->Result<Vec<PathBuf>,AssetIoError>{
+        let mut entries = BTreeSet::new();
+        for (io,relative) in self.resolve(path){
+            if let Ok(io_entries) = io.read_dir(&relative){
+                entries.extend(io_entries.iter().filter_map(|entry|entry.file_name()).map(|name|path.join(name)));
+            }
+        }
+        if entries.is_empty(){
+            return Err(AssetIoError::NotFound(path.to_path_buf()));
+        }
+        return Ok(entries.into_iter().collect());
+    }
+
+    /// Writes through the first mounted io that supports writing, `res://` ones usually do not.
+    fn write(&self,path: &Path,bytes: &[u8])->Result<(),AssetIoError>{
+        for (io,relative) in self.resolve(path){
+            match io.write(&relative,bytes){
+                Err(AssetIoError::Unsupported(_))=>continue,
+                result=>return result,
+            }
+        }
+        return Err(AssetIoError::Unsupported("writing"));
+    }
+
+    /// Succeeds when any mounted io can watch.
+    fn watch_for_changes(&self)->Result<(),AssetIoError>{
+        let mut result = Err(AssetIoError::Unsupported("watching for changes"));
+        for mount in self.mounts.read().unwrap().iter(){
+            match mount.io.watch_for_changes(){
+                Ok(())=>result = Ok(()),
+                Err(AssetIoError::Unsupported(_))=>{},
+                Err(error)=>{
+                    if result.is_err(){
+                        result = Err(error);
+                    }
+                },
+            }
+        }
+        return result;
+    }
+
+    fn changed_paths(&self)->Vec<PathBuf>{
+        let mut changed_paths = Vec::new();
+        for mount in self.mounts.read().unwrap().iter(){
+            for path in mount.io.changed_paths(){
+                let path = mount.prefix.join(path);
+                if mount.scheme == RES_SCHEME{
+                    changed_paths.push(path);
+                }else{
+                    changed_paths.push(PathBuf::from(format!("{}://{}",mount.scheme,path.to_string_lossy())));
+                }
+            }
+        }
+        return changed_paths;
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn memory_io(files: &[(&str,&str)])->Arc<dyn AssetIo>{
+        let io = MemoryAssetIo::default();
+        for (path,content) in files{
+            io.insert(path,*content);
+        }
+        return Arc::new(io);
+    }
+
+    fn read(io: &VirtualAssetIo,path: &str)->String{
+        return String::from_utf8(io.read(Path::new(path)).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn higher_priority_mount_wins(){
+        let io = VirtualAssetIo::new(Box::new(MemoryAssetIo::default()));
+        io.mount("mod","",memory_io(&[("a.txt","patch"),("b.txt","patch")]),10);
+        io.mount("mod","",memory_io(&[("a.txt","base"),("c.txt","base")]),0);
+        assert_eq!(read(&io,"mod://a.txt"),"patch");
+        assert_eq!(read(&io,"mod://b.txt"),"patch");
+        // files missing from the higher mount fall through to the lower one
+        assert_eq!(read(&io,"mod://c.txt"),"base");
+        assert!(matches!(io.read(Path::new("mod://d.txt")),Err(AssetIoError::NotFound(_))));
+    }
+
+    #[test]
+    fn later_mount_wins_at_equal_priority(){
+        let io = VirtualAssetIo::new(Box::new(MemoryAssetIo::default()));
+        io.mount("mod","",memory_io(&[("a.txt","first"),("b.txt","first")]),0);
+        io.mount("mod","",memory_io(&[("a.txt","second")]),0);
+        assert_eq!(read(&io,"mod://a.txt"),"second");
+        assert_eq!(read(&io,"mod://b.txt"),"first");
+
+        assert!(io.unmount("mod",""));
+        assert!(!io.exists(Path::new("mod://a.txt")));
+    }
+
+    #[test]
+    fn mounts_serve_paths_below_their_prefix(){
+        let io = VirtualAssetIo::new(Box::new(MemoryAssetIo::default()));
+        io.mount(RES_SCHEME,"dlc",memory_io(&[("a.txt","dlc")]),0);
+        assert_eq!(read(&io,"res://dlc/a.txt"),"dlc");
+        assert_eq!(read(&io,"dlc/a.txt"),"dlc");
+        assert!(!io.exists(Path::new("a.txt")));
+    }
+}
@@ -0,0 +1,65 @@
+use super::asset_path::{AssetPath};
+use super::handle::{Handle,HandleId};
+use bevy::asset::{Asset,AssetDynamic};
+
+/// Passed to `AssetLoader::load`, lets a loader emit labeled sub-assets and declare the
+/// other assets its asset needs.
+pub struct LoadContext<'a>{
+    asset_path: &'a AssetPath,
+    labeled_assets: Vec<(String,Box<dyn AssetDynamic>)>,
+    dependencies: Vec<AssetPath>,
+}
+
+/// Everything one loader run produced.
+pub(crate) struct LoadedAsset{
+    pub(crate) asset: Box<dyn AssetDynamic>,
+    pub(crate) labeled_assets: Vec<(String,Box<dyn AssetDynamic>)>,
+    pub(crate) dependencies: Vec<AssetPath>,
+}
+
+impl<'a> LoadContext<'a>{
+    pub(crate) fn new(asset_path: &'a AssetPath)->Self{
+        Self{
+            asset_path,
+            labeled_assets: Vec::new(),
+            dependencies: Vec::new(),
+        }
+    }
+
+    /// Path of the file being loaded.
+    pub fn path(&self)->&AssetPath{
+        self.asset_path
+    }
+
+    /// Emits `asset` as `<path>#<label>` and returns a weak handle to it. Labeled assets
+    /// live as long as the asset of the file, which stays alive while any of them is used.
+    pub fn set_labeled_asset<T: Asset>(&mut self,label: &str,asset: T)->Handle<T>{
+        self.labeled_assets.retain(|(other,_)|other!=label);
+        self.labeled_assets.push((label.to_string(),Box::new(asset)));
+        return Handle::weak(HandleId::from(self.asset_path.with_label(label)));
+    }
+
+    pub fn has_labeled_asset(&self,label: &str)->bool{
+        self.labeled_assets.iter().any(|(other,_)|other==label)
+    }
+
+    /// Has the server load `path` too, with whichever loader its extension picks. The
+    /// dependency stays loaded while this asset is, see `AssetServer::get_dependency_load_state`.
+    /// Returns a weak handle to it.
+    pub fn add_dependency<T: Asset,P: Into<AssetPath>>(&mut self,path: P)->Handle<T>{
+        let path = path.into();
+        let handle = Handle::weak(HandleId::from(path.clone()));
+        if !self.dependencies.contains(&path){
+            self.dependencies.push(path);
+        }
+        return handle;
+    }
+
+    pub(crate) fn finish(self,asset: Box<dyn AssetDynamic>)->LoadedAsset{
+        LoadedAsset{
+            asset,
+            labeled_assets: self.labeled_assets,
+            dependencies: self.dependencies,
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use serde::{Serialize};
+
+/// Load state of the asset behind a handle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum LoadState {
+    /// The asset was never requested, or it has been freed.
+    NotLoaded,
+    /// The asset is being read and decoded on the loader pool.
+    Loading,
+    /// The asset is available in `Assets<T>`.
+    Loaded,
+    /// The asset could not be loaded.
+    Failed,
+}
+
+impl Default for LoadState {
+    fn default() -> Self {
+        Self::NotLoaded
+    }
+}
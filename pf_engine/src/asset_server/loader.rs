@@ -1,6 +1,184 @@
 use super::asset_path::{AssetPath};
-use std::any::{Any};
-pub trait AssetLoader:Send + Sync{
-    fn load(&self,asset_path:&AssetPath) -> Box<dyn Any>;
+use super::error::{AssetLoadError};
+use super::load_context::{LoadContext};
+use bevy::asset::{Asset,AssetDynamic};
+use bevy::utils::Uuid;
+use serde::de::DeserializeOwned;
+use std::sync::{Arc};
+
+pub trait AssetLoader:Send + Sync + 'static{
+    /// What `load` produces, `AssetServer::load::<T>` only picks loaders of `T`.
+    type Asset: Asset;
+    /// Read from the RON file `<asset>.meta` next to the asset, `Default` when there is none.
+    type Settings: Default + DeserializeOwned + Send + Sync + 'static;
+
+    /// `bytes` is the whole file, read through the server's `AssetIo`. Returns the asset of the
+    /// file itself, sub-assets and dependencies go through `load_context`.
+    fn load(&self,bytes:&[u8],settings:&Self::Settings,load_context:&mut LoadContext) -> Result<Self::Asset,AssetLoadError>;
+    /// Lowercase extensions without the leading dot, compound ones such as `tex.ktx2` win
+    /// over shorter ones.
     fn extensions(&self)->&[&str];
+    /// Tells whether `bytes` look like a file of this loader, usually by its magic bytes.
+    /// Only asked when the extension of a path is missing, unknown or claimed by several loaders.
+    fn sniff(&self,_bytes:&[u8])->bool{
+        return false;
+    }
+}
+
+/// Object safe side of `AssetLoader`, this is what the asset server keeps.
+pub trait ErasedAssetLoader:Send + Sync{
+    /// `meta` is the content of the `.meta` file, if the asset has one.
+    fn load(&self,bytes:&[u8],meta:Option<&[u8]>,load_context:&mut LoadContext) -> Result<Box<dyn AssetDynamic>,AssetLoadError>;
+    fn extensions(&self)->&[&str];
+    fn sniff(&self,bytes:&[u8])->bool;
+    fn type_name(&self)->&'static str;
+    fn asset_type_uuid(&self)->Uuid;
+    fn asset_type_name(&self)->&'static str;
+}
+
+impl<L: AssetLoader> ErasedAssetLoader for L{
+    fn load(&self,bytes:&[u8],meta:Option<&[u8]>,load_context:&mut LoadContext) -> Result<Box<dyn AssetDynamic>,AssetLoadError>{
+        let settings = match meta{
+            Some(meta)=>ron::de::from_bytes::<L::Settings>(meta).map_err(|e|AssetLoadError::InvalidMeta{
+                path:load_context.path().clone(),
+                message:e.to_string(),
+            })?,
+            None=>L::Settings::default(),
+        };
+        let asset = AssetLoader::load(self,bytes,&settings,load_context)?;
+        return Ok(Box::new(asset));
+    }
+
+    fn extensions(&self)->&[&str]{
+        return AssetLoader::extensions(self);
+    }
+
+    fn sniff(&self,bytes:&[u8])->bool{
+        return AssetLoader::sniff(self,bytes);
+    }
+
+    fn type_name(&self)->&'static str{
+        return std::any::type_name::<L>();
+    }
+
+    fn asset_type_uuid(&self)->Uuid{
+        return <L::Asset as bevy::reflect::TypeUuid>::TYPE_UUID;
+    }
+
+    fn asset_type_name(&self)->&'static str{
+        return std::any::type_name::<L::Asset>();
+    }
+}
+
+/// Loaders that may load a path, picked from its extensions before the file is read.
+pub(crate) enum LoaderSelection{
+    /// Exactly one loader claims the extension.
+    Loader(Arc<dyn ErasedAssetLoader>),
+    /// No loader or several claim it, the candidates whose `sniff` accepts the bytes are
+    /// considered. `error` is reported when none does.
+    Sniff{
+        candidates: Vec<Arc<dyn ErasedAssetLoader>>,
+        error: AssetLoadError,
+    },
+}
+
+impl LoaderSelection{
+    pub(crate) fn resolve(self,bytes:&[u8],asset_path:&AssetPath)->Result<Arc<dyn ErasedAssetLoader>,AssetLoadError>{
+        let (candidates,error) = match self{
+            LoaderSelection::Loader(loader)=>return Ok(loader),
+            LoaderSelection::Sniff{candidates,error}=>(candidates,error),
+        };
+        let mut accepted: Vec<_> = candidates.into_iter().filter(|loader|loader.sniff(bytes)).collect();
+        return match accepted.len(){
+            0=>Err(error),
+            1=>Ok(accepted.pop().unwrap()),
+            _=>Err(AssetLoadError::AmbiguousLoader{
+                path:asset_path.clone(),
+                loaders:accepted.iter().map(|loader|loader.type_name()).collect(),
+            }),
+        };
+    }
+}
+
+pub(crate) fn claims_extension(loader: &dyn ErasedAssetLoader,extension: &str)->bool{
+    return loader.extensions().iter().any(|ext|ext.eq_ignore_ascii_case(extension));
+}
+
+/// Path of the settings file that belongs to `asset_path`.
+pub fn meta_path(asset_path: &AssetPath)->String{
+    format!("{}.meta",asset_path.path())
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use super::super::test_util::{TextAsset};
+
+    /// Claims `bin` files and recognizes them by `magic`.
+    struct MagicLoader{
+        magic: &'static [u8],
+    }
+
+    impl AssetLoader for MagicLoader{
+        type Asset = TextAsset;
+        type Settings = ();
+
+        fn load(&self,_bytes:&[u8],_settings:&(),_load_context:&mut LoadContext)->Result<TextAsset,AssetLoadError>{
+            return Ok(TextAsset(String::new()));
+        }
+
+        fn extensions(&self)->&[&str]{
+            return &["bin","mesh.ron"];
+        }
+
+        fn sniff(&self,bytes:&[u8])->bool{
+            return bytes.starts_with(self.magic);
+        }
+    }
+
+    fn loader(magic: &'static [u8])->Arc<dyn ErasedAssetLoader>{
+        return Arc::new(MagicLoader{magic});
+    }
+
+    fn sniff(candidates: &[Arc<dyn ErasedAssetLoader>],bytes: &[u8])->Result<Arc<dyn ErasedAssetLoader>,AssetLoadError>{
+        let path = AssetPath::from("a.bin");
+        let selection = LoaderSelection::Sniff{
+            candidates:candidates.to_vec(),
+            error:AssetLoadError::MissingExtension(path.clone()),
+        };
+        return selection.resolve(bytes,&path);
+    }
+
+    #[test]
+    fn extensions_are_claimed_ignoring_case(){
+        let loader = loader(b"");
+        assert!(claims_extension(&*loader,"bin"));
+        assert!(claims_extension(&*loader,"BIN"));
+        assert!(claims_extension(&*loader,"mesh.ron"));
+        assert!(!claims_extension(&*loader,"ron"));
+        assert!(!claims_extension(&*loader,"bi"));
+    }
+
+    #[test]
+    fn sniffing_picks_the_loader_of_the_magic_bytes(){
+        let candidates = [loader(b"\x89PNG"),loader(b"\xABKTX")];
+        let png = sniff(&candidates,b"\x89PNG\r\n").unwrap();
+        assert!(png.sniff(b"\x89PNG") && !png.sniff(b"\xABKTX"));
+        let ktx = sniff(&candidates,b"\xABKTX 20").unwrap();
+        assert!(ktx.sniff(b"\xABKTX") && !ktx.sniff(b"\x89PNG"));
+        assert!(matches!(sniff(&candidates,b"GIF89a"),Err(AssetLoadError::MissingExtension(_))));
+    }
+
+    #[test]
+    fn sniffing_fails_when_several_loaders_accept_the_bytes(){
+        let candidates = [loader(b"\x89"),loader(b"\x89PNG")];
+        assert!(matches!(sniff(&candidates,b"\x89PNG"),Err(AssetLoadError::AmbiguousLoader{..})));
+    }
+
+    #[test]
+    fn single_claiming_loader_is_used_without_sniffing(){
+        let path = AssetPath::from("a.bin");
+        let loader = LoaderSelection::Loader(loader(b"\x89PNG")).resolve(b"GIF89a",&path).unwrap();
+        assert!(loader.sniff(b"\x89PNG"));
+    }
 }
@@ -8,5 +8,29 @@ pub mod assets;
 pub mod plugin;
 pub mod asset_lifecycle;
 pub mod asset_stage;
+pub mod load_state;
+pub mod error;
+pub mod asset_event;
+pub mod io;
+pub mod pack;
+pub mod cook_manifest;
+pub mod load_context;
+pub mod asset_group;
+pub mod asset_memory;
+pub mod diagnostics;
+pub mod saver;
+#[cfg(test)]
+pub(crate) mod test_util;
 
 pub use assets::{Assets};
+pub use load_state::{LoadState};
+pub use asset_memory::{AssetMemory,AssetMemoryUsage};
+pub use diagnostics::{AssetDiagnostics,AssetDiagnostic,DumpAssetDiagnostics};
+pub use load_context::{LoadContext};
+pub use asset_group::{AssetGroups,AssetGroupId,AssetGroupReady,AssetGroupProgress,AssetGroupManifest};
+pub use error::{AssetLoadError,AssetSaveError};
+pub use saver::{AssetSaver};
+pub use asset_event::{AssetLoadFailed,AssetEvent};
+pub use io::{AssetIo,AssetIoError,AssetReader,FileAssetIo,MemoryAssetIo,VirtualAssetIo};
+pub use asset_path::{AssetPath,RES_SCHEME,USER_SCHEME,MEM_SCHEME};
+pub use cook_manifest::{CookManifest,CookManifestError};
@@ -0,0 +1,118 @@
+use super::{PackCompression,MAGIC,VERSION};
+use crate::utils::fnv1a_hash;
+use std::io::{self,Write};
+use std::path::{Path};
+
+struct PendingEntry{
+    path: String,
+    bytes: Vec<u8>,
+    compression: PackCompression,
+}
+
+/// Collects files and writes them out as an asset pack.
+pub struct AssetPackBuilder{
+    entries: Vec<PendingEntry>,
+    compression: PackCompression,
+}
+
+impl Default for AssetPackBuilder{
+    fn default()->Self{
+        Self{
+            entries: Default::default(),
+            compression: PackCompression::None,
+        }
+    }
+}
+
+impl AssetPackBuilder{
+    pub fn new()->Self{
+        Self::default()
+    }
+
+    /// Compression of the entries added from now on.
+    pub fn with_compression(mut self,compression: PackCompression)->Self{
+        self.compression = compression;
+        return self;
+    }
+
+    /// Adds a file under its asset path, a path added twice keeps the last bytes.
+    pub fn add<P: Into<String>>(&mut self,path: P,bytes: Vec<u8>)->&mut Self{
+        let compression = self.compression;
+        return self.add_with_compression(path,bytes,compression);
+    }
+
+    pub fn add_with_compression<P: Into<String>>(&mut self,path: P,bytes: Vec<u8>,compression: PackCompression)->&mut Self{
+        let path = path.into();
+        self.entries.retain(|entry|entry.path != path);
+        self.entries.push(PendingEntry{
+            path,
+            bytes,
+            compression,
+        });
+        return self;
+    }
+
+    /// Adds every file below `root`, named by its path relative to `root` with `/` separators.
+    pub fn add_dir<P: AsRef<Path>>(&mut self,root: P)->io::Result<&mut Self>{
+        let root = root.as_ref();
+        let mut pending_dirs = vec![root.to_path_buf()];
+        while let Some(dir) = pending_dirs.pop(){
+            for entry in std::fs::read_dir(&dir)?{
+                let path = entry?.path();
+                if path.is_dir(){
+                    pending_dirs.push(path);
+                    continue;
+                }
+                let relative_path = path.strip_prefix(root)
+                    .expect("walked paths are below the root")
+                    .to_string_lossy()
+                    .replace('\\',"/");
+                let bytes = std::fs::read(&path)?;
+                self.add(relative_path,bytes);
+            }
+        }
+        return Ok(self);
+    }
+
+    pub fn write_to<W: Write>(&self,writer: &mut W)->io::Result<()>{
+        let mut index = Vec::new();
+        let mut data = Vec::new();
+        for entry in self.entries.iter(){
+            let (compression,stored) = match entry.compression{
+                PackCompression::None=>(PackCompression::None,entry.bytes.clone()),
+                PackCompression::Deflate=>{
+                    let compressed = miniz_oxide::deflate::compress_to_vec(&entry.bytes,6);
+                    // not worth inflating on the device
+                    if compressed.len() < entry.bytes.len(){
+                        (PackCompression::Deflate,compressed)
+                    }else{
+                        (PackCompression::None,entry.bytes.clone())
+                    }
+                },
+            };
+            let path_len = u16::try_from(entry.path.len())
+                .map_err(|_|io::Error::new(io::ErrorKind::InvalidInput,format!("path too long: {}",entry.path)))?;
+            index.extend_from_slice(&path_len.to_le_bytes());
+            index.extend_from_slice(entry.path.as_bytes());
+            index.push(compression as u8);
+            index.extend_from_slice(&(data.len() as u64).to_le_bytes());
+            index.extend_from_slice(&(stored.len() as u64).to_le_bytes());
+            index.extend_from_slice(&(entry.bytes.len() as u64).to_le_bytes());
+            index.extend_from_slice(&fnv1a_hash(&entry.bytes).to_le_bytes());
+            data.extend_from_slice(&stored);
+        }
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&(self.entries.len() as u32).to_le_bytes())?;
+        writer.write_all(&index)?;
+        writer.write_all(&data)?;
+        return Ok(());
+    }
+
+    pub fn build(&self)->Vec<u8>{
+        let mut bytes = Vec::new();
+        self.write_to(&mut bytes).expect("writing to a Vec can not fail");
+        return bytes;
+    }
+}
@@ -0,0 +1,76 @@
+//! Asset packs bundle many asset files into one indexed archive, so a device opens a single
+//! file instead of one per asset.
+//!
+//! Layout, all integers little endian:
+//! ```text
+//! magic        b"PFPK"
+//! version      u32
+//! entry count  u32
+//! entries      path length u16, path utf-8, compression u8,
+//!              offset u64, stored size u64, size u64, content hash u64
+//! data         the stored bytes of every entry, offsets are relative to here
+//! ```
+//! The content hash is `utils::fnv1a_hash` of the uncompressed bytes.
+
+mod builder;
+mod reader;
+
+pub use builder::{AssetPackBuilder};
+pub use reader::{AssetPack,PackAssetIo};
+
+use super::io::{AssetIoError};
+
+pub(crate) const MAGIC: &[u8; 4] = b"PFPK";
+pub(crate) const VERSION: u32 = 1;
+
+/// How the bytes of an entry are stored.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum PackCompression{
+    None = 0,
+    /// Raw deflate stream.
+    Deflate = 1,
+}
+
+impl PackCompression{
+    fn from_id(id: u8)->Result<Self,AssetPackError>{
+        match id{
+            0=>Ok(Self::None),
+            1=>Ok(Self::Deflate),
+            _=>Err(AssetPackError::UnknownCompression(id)),
+        }
+    }
+}
+
+/// Index record of one file in a pack.
+#[derive(Clone, Debug)]
+pub struct PackEntry{
+    pub compression: PackCompression,
+    pub offset: u64,
+    pub stored_size: u64,
+    pub size: u64,
+    pub content_hash: u64,
+}
+
+/// Set of possible asset pack errors.
+#[derive(Debug, thiserror::Error)]
+pub enum AssetPackError{
+    #[error("Not an asset pack")]
+    InvalidMagic,
+    #[error("Unsupported asset pack version {0}")]
+    UnsupportedVersion(u32),
+    #[error("Asset pack is truncated")]
+    Truncated,
+    #[error("Asset pack contains a path that is not valid utf-8")]
+    InvalidPath,
+    #[error("Unknown compression id {0}")]
+    UnknownCompression(u8),
+    #[error("No entry {0:?} in asset pack")]
+    NotFound(String),
+    #[error("Failed to decompress {path:?}: {message}")]
+    Decompress{ path: String, message: String },
+    #[error("Content hash of {0:?} does not match, the pack is corrupted")]
+    HashMismatch(String),
+    #[error("Failed to read asset pack: {0}")]
+    Io(#[from] AssetIoError),
+}
@@ -0,0 +1,290 @@
+use super::{AssetPackError,PackCompression,PackEntry,MAGIC,VERSION};
+use crate::asset_server::io::{AssetIo,AssetIoError,AssetReader};
+use crate::utils::fnv1a_hash;
+use std::collections::{BTreeSet,HashMap};
+use std::io::{self,Read,Seek,SeekFrom};
+use std::path::{Path,PathBuf};
+use std::sync::{Arc,Mutex};
+
+/// An asset pack whose index is in memory. Entries are read from the pack as they are asked
+/// for, so a pack opened from a file is never read whole.
+pub struct AssetPack{
+    reader: Mutex<Box<dyn AssetReader>>,
+    len: u64,
+    data_offset: u64,
+    entries: HashMap<String,PackEntry>,
+}
+
+/// Reads the index, counting the bytes so the data offset is known at its end.
+struct IndexReader<R>{
+    reader: R,
+    position: u64,
+}
+
+impl<R: Read> IndexReader<R>{
+    fn take(&mut self,len: usize)->Result<Vec<u8>,AssetPackError>{
+        let mut bytes = vec![0u8;len];
+        self.reader.read_exact(&mut bytes).map_err(read_error)?;
+        self.position += len as u64;
+        return Ok(bytes);
+    }
+
+    fn read_u8(&mut self)->Result<u8,AssetPackError>{
+        return Ok(self.take(1)?[0]);
+    }
+
+    fn read_u16(&mut self)->Result<u16,AssetPackError>{
+        return Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()));
+    }
+
+    fn read_u32(&mut self)->Result<u32,AssetPackError>{
+        return Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()));
+    }
+
+    fn read_u64(&mut self)->Result<u64,AssetPackError>{
+        return Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()));
+    }
+}
+
+fn read_error(error: io::Error)->AssetPackError{
+    match error.kind(){
+        io::ErrorKind::UnexpectedEof=>AssetPackError::Truncated,
+        _=>AssetPackError::Io(AssetIoError::Io(error)),
+    }
+}
+
+impl AssetPack{
+    /// A pack held in memory.
+    pub fn from_bytes(bytes: Vec<u8>)->Result<Self,AssetPackError>{
+        return Self::from_reader(Box::new(io::Cursor::new(bytes)));
+    }
+
+    /// Opens a pack file through `asset_io`, only its index is read now.
+    pub fn open<P: AsRef<Path>>(asset_io: &dyn AssetIo,path: P)->Result<Self,AssetPackError>{
+        return Self::from_reader(asset_io.open(path.as_ref())?);
+    }
+
+    /// Parses the index, entry bytes are only read and validated when they are asked for.
+    pub fn from_reader(mut reader: Box<dyn AssetReader>)->Result<Self,AssetPackError>{
+        let len = reader.seek(SeekFrom::End(0)).map_err(read_error)?;
+        reader.seek(SeekFrom::Start(0)).map_err(read_error)?;
+        let mut index = IndexReader{
+            reader: io::BufReader::new(&mut reader),
+            position: 0,
+        };
+        if index.take(4)? != MAGIC{
+            return Err(AssetPackError::InvalidMagic);
+        }
+        let version = index.read_u32()?;
+        if version != VERSION{
+            return Err(AssetPackError::UnsupportedVersion(version));
+        }
+        let entry_count = index.read_u32()?;
+        let mut entries = HashMap::new();
+        for _ in 0..entry_count{
+            let path_len = index.read_u16()? as usize;
+            let path = String::from_utf8(index.take(path_len)?)
+                .map_err(|_|AssetPackError::InvalidPath)?;
+            let entry = PackEntry{
+                compression: PackCompression::from_id(index.read_u8()?)?,
+                offset: index.read_u64()?,
+                stored_size: index.read_u64()?,
+                size: index.read_u64()?,
+                content_hash: index.read_u64()?,
+            };
+            entries.insert(path,entry);
+        }
+        let data_offset = index.position;
+        return Ok(Self{
+            reader: Mutex::new(reader),
+            len,
+            data_offset,
+            entries,
+        });
+    }
+
+    pub fn entry(&self,path: &str)->Option<&PackEntry>{
+        self.entries.get(path)
+    }
+
+    pub fn paths(&self)->impl Iterator<Item=&str>{
+        self.entries.keys().map(|path|path.as_str())
+    }
+
+    /// Returns the uncompressed bytes of an entry after checking its content hash.
+    pub fn read(&self,path: &str)->Result<Vec<u8>,AssetPackError>{
+        let entry = self.entries.get(path).ok_or_else(||AssetPackError::NotFound(path.to_string()))?;
+        // a corrupted index may hold offsets and sizes anywhere up to u64::MAX
+        let begin = self.data_offset.checked_add(entry.offset).ok_or(AssetPackError::Truncated)?;
+        let end = begin.checked_add(entry.stored_size).ok_or(AssetPackError::Truncated)?;
+        if end > self.len{
+            return Err(AssetPackError::Truncated);
+        }
+        let stored_size = usize::try_from(entry.stored_size).map_err(|_|AssetPackError::Truncated)?;
+        let mut stored = vec![0u8;stored_size];
+        {
+            let mut reader = self.reader.lock().unwrap();
+            reader.seek(SeekFrom::Start(begin)).map_err(read_error)?;
+            reader.read_exact(&mut stored).map_err(read_error)?;
+        }
+        let bytes = match entry.compression{
+            PackCompression::None=>stored,
+            PackCompression::Deflate=>miniz_oxide::inflate::decompress_to_vec(&stored).map_err(|e|AssetPackError::Decompress{
+                path: path.to_string(),
+                message: format!("{:?}",e),
+            })?,
+        };
+        if bytes.len() as u64 != entry.size || fnv1a_hash(&bytes) != entry.content_hash{
+            return Err(AssetPackError::HashMismatch(path.to_string()));
+        }
+        return Ok(bytes);
+    }
+}
+
+fn pack_path(path: &Path)->String{
+    path.to_string_lossy().replace('\\',"/")
+}
+
+/// Serves files from a pack, paths missing from it are read from `fallback`.
+pub struct PackAssetIo{
+    pack: AssetPack,
+    fallback: Option<Arc<dyn AssetIo>>,
+}
+
+impl PackAssetIo{
+    pub fn new(pack: AssetPack,fallback: Option<Arc<dyn AssetIo>>)->Self{
+        Self{pack,fallback}
+    }
+}
+
+impl AssetIo for PackAssetIo{
+    fn read(&self,path: &Path)->Result<Vec<u8>,AssetIoError>{
+        let pack_path = pack_path(path);
+        if self.pack.entry(&pack_path).is_some(){
+            return self.pack.read(&pack_path)
+                .map_err(|e|AssetIoError::Io(io::Error::new(io::ErrorKind::InvalidData,e)));
+        }
+        match &self.fallback{
+            Some(fallback)=>fallback.read(path),
+            None=>Err(AssetIoError::NotFound(path.to_path_buf())),
+        }
+    }
+
+    fn exists(&self,path: &Path)->bool{
+        let prefix = Path::new(path);
+        return self.pack.paths().any(|entry|Path::new(entry).starts_with(prefix))
+            || self.fallback.as_ref().map_or(false,|fallback|fallback.exists(path));
+    }
+
+    fn read_dir(&self,path: &Path)->Result<Vec<PathBuf>,AssetIoError>{
+        let mut entries: BTreeSet<PathBuf> = self.pack.paths()
+            .filter_map(|entry|Path::new(entry).strip_prefix(path).ok())
+            .filter_map(|relative|relative.components().next())
+            .map(|component|path.join(component))
+            .collect();
+        if let Some(fallback) = &self.fallback{
+            if let Ok(fallback_entries) = fallback.read_dir(path){
+                entries.extend(fallback_entries);
+            }
+        }
+        if entries.is_empty(){
+            return Err(AssetIoError::NotFound(path.to_path_buf()));
+        }
+        return Ok(entries.into_iter().collect());
+    }
+
+    /// Packs are read only, files go to the fallback. A path that is in the pack keeps
+    /// reading from the pack.
+    fn write(&self,path: &Path,bytes: &[u8])->Result<(),AssetIoError>{
+        match &self.fallback{
+            Some(fallback)=>fallback.write(path,bytes),
+            None=>Err(AssetIoError::Unsupported("writing")),
+        }
+    }
+
+    fn watch_for_changes(&self)->Result<(),AssetIoError>{
+        match &self.fallback{
+            Some(fallback)=>fallback.watch_for_changes(),
+            None=>Err(AssetIoError::Unsupported("watching for changes")),
+        }
+    }
+
+    fn changed_paths(&self)->Vec<PathBuf>{
+        return self.fallback.as_ref()
+            .map(|fallback|fallback.changed_paths())
+            .unwrap_or_default();
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use super::super::builder::{AssetPackBuilder};
+    use crate::asset_server::io::{FileAssetIo};
+
+    /// `a.txt` stored as is, `b.bin` deflated.
+    fn pack_bytes()->Vec<u8>{
+        let mut builder = AssetPackBuilder::new();
+        builder.add("a.txt",b"hello".to_vec());
+        builder.add_with_compression("b.bin",vec![7u8;256],PackCompression::Deflate);
+        return builder.build();
+    }
+
+    /// Position of the offset of `a.txt`, the first entry of `pack_bytes`: after the header,
+    /// the path length, the path and the compression id.
+    const FIRST_OFFSET: usize = 12 + 2 + 5 + 1;
+
+    #[test]
+    fn written_pack_reads_back(){
+        let pack = AssetPack::from_bytes(pack_bytes()).unwrap();
+        assert_eq!(pack.read("a.txt").unwrap(),b"hello");
+        assert_eq!(pack.read("b.bin").unwrap(),vec![7u8;256]);
+        assert_eq!(pack.entry("b.bin").unwrap().compression,PackCompression::Deflate);
+        assert!(matches!(pack.read("c.txt"),Err(AssetPackError::NotFound(_))));
+    }
+
+    #[test]
+    fn pack_file_reads_back(){
+        let dir = std::env::temp_dir().join(format!("pf_pack_test_{}",std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("assets.pfpk"),pack_bytes()).unwrap();
+        let pack = AssetPack::open(&FileAssetIo::new(&dir),"assets.pfpk").unwrap();
+        assert_eq!(pack.read("a.txt").unwrap(),b"hello");
+        assert_eq!(pack.read("b.bin").unwrap(),vec![7u8;256]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn truncated_index_fails(){
+        let bytes = pack_bytes();
+        for len in [0,3,8,12,FIRST_OFFSET + 4]{
+            assert!(matches!(AssetPack::from_bytes(bytes[..len].to_vec()),Err(AssetPackError::Truncated)));
+        }
+    }
+
+    #[test]
+    fn entry_out_of_the_pack_fails(){
+        let mut bytes = pack_bytes();
+        bytes[FIRST_OFFSET..FIRST_OFFSET + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        let pack = AssetPack::from_bytes(bytes).unwrap();
+        assert!(matches!(pack.read("a.txt"),Err(AssetPackError::Truncated)));
+
+        let mut bytes = pack_bytes();
+        bytes[FIRST_OFFSET + 8..FIRST_OFFSET + 16].copy_from_slice(&4096u64.to_le_bytes());
+        let pack = AssetPack::from_bytes(bytes).unwrap();
+        assert!(matches!(pack.read("a.txt"),Err(AssetPackError::Truncated)));
+        // the other entry is still readable
+        assert_eq!(pack.read("b.bin").unwrap(),vec![7u8;256]);
+    }
+
+    #[test]
+    fn bad_magic_or_version_fails(){
+        let mut bytes = pack_bytes();
+        bytes[0] = b'X';
+        assert!(matches!(AssetPack::from_bytes(bytes),Err(AssetPackError::InvalidMagic)));
+
+        let mut bytes = pack_bytes();
+        bytes[4..8].copy_from_slice(&(VERSION + 1).to_le_bytes());
+        assert!(matches!(AssetPack::from_bytes(bytes),Err(AssetPackError::UnsupportedVersion(version)) if version == VERSION + 1));
+    }
+}
@@ -1,17 +1,47 @@
-use bevy::prelude::{App,Plugin,CoreStage,SystemStage};
-use super::asset_server::{AssetServer,free_unused_assets_system};
+use bevy::prelude::{App,Plugin,CoreStage,SystemStage,ParallelSystemDescriptorCoercion};
+use super::asset_server::{AssetServer,FreeUnusedAssets,free_unused_assets_system,asset_io_watcher_system,load_results_system,register_asset,add_loader};
+use super::asset_group::{AssetGroups,AssetGroupReady,AssetGroupManifest,AssetGroupManifestLoader,asset_group_system};
 use super::asset_stage::{AssetStage};
+use super::asset_event::{AssetLoadFailed};
+use super::diagnostics::{AssetDiagnostics,DumpAssetDiagnostics,CollectAssetDiagnostics,asset_server_diagnostics_system,dump_asset_diagnostics_system};
 pub struct AssetPlugin{
 
 }
 
 impl Plugin for AssetPlugin{
     fn build(&self,app: &mut App){
-        app.insert_resource(AssetServer::default());
+        // keep a server inserted up front, e.g. one reading from a `MemoryAssetIo`
+        if !app.world.contains_resource::<AssetServer>(){
+            app.insert_resource(AssetServer::default());
+        }
+        app.add_event::<AssetLoadFailed>();
         app.add_system_to_stage(
             CoreStage::PreUpdate,
-            free_unused_assets_system,
+            free_unused_assets_system.label(FreeUnusedAssets),
+        );
+        // does nothing until `AssetServer::watch_for_changes` is called
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            asset_io_watcher_system,
+        );
+        // routes finished loads to the `Assets<T>` storages updated in `AssetStage::UpdateAssets`
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            load_results_system,
         );
         app.add_stage(AssetStage::UpdateAssets,SystemStage::parallel());
+        app.add_stage_after(AssetStage::UpdateAssets,AssetStage::AssetEvents,SystemStage::parallel());
+
+        app.init_resource::<AssetDiagnostics>();
+        app.add_event::<DumpAssetDiagnostics>();
+        app.add_system_to_stage(AssetStage::AssetEvents,asset_server_diagnostics_system.label(CollectAssetDiagnostics));
+        app.add_system_to_stage(AssetStage::AssetEvents,dump_asset_diagnostics_system.after(CollectAssetDiagnostics));
+
+        register_asset::<AssetGroupManifest>(app);
+        add_loader(app,AssetGroupManifestLoader::default());
+        app.init_resource::<AssetGroups>();
+        app.add_event::<AssetGroupReady>();
+        // load states of this frame are final once `AssetStage::UpdateAssets` ran
+        app.add_system_to_stage(AssetStage::AssetEvents,asset_group_system);
     }
 }
@@ -0,0 +1,40 @@
+use super::asset_path::{AssetPath};
+use super::error::{AssetSaveError};
+use bevy::asset::{Asset,AssetDynamic};
+use bevy::utils::Uuid;
+
+/// Counterpart of `AssetLoader`, turns an asset back into the bytes of a file.
+pub trait AssetSaver:Send + Sync + 'static{
+    type Asset: Asset;
+
+    /// Lowercase extensions without the leading dot, the longest one a path ends with picks
+    /// the saver among those of the asset type.
+    fn extensions(&self)->&[&str];
+    fn save(&self,asset: &Self::Asset,asset_path: &AssetPath)->Result<Vec<u8>,AssetSaveError>;
+}
+
+/// Object safe side of `AssetSaver`, this is what the asset server keeps.
+pub(crate) trait ErasedAssetSaver:Send + Sync{
+    /// `asset` is of the type `asset_type_uuid` names.
+    fn save(&self,asset: &dyn AssetDynamic,asset_path: &AssetPath)->Result<Vec<u8>,AssetSaveError>;
+    fn extensions(&self)->&[&str];
+    fn asset_type_uuid(&self)->Uuid;
+}
+
+impl<S: AssetSaver> ErasedAssetSaver for S{
+    fn save(&self,asset: &dyn AssetDynamic,asset_path: &AssetPath)->Result<Vec<u8>,AssetSaveError>{
+        let asset = match asset.downcast_ref::<S::Asset>(){
+            Some(asset)=>asset,
+            None=>unreachable!("savers are picked by asset type"),
+        };
+        return AssetSaver::save(self,asset,asset_path);
+    }
+
+    fn extensions(&self)->&[&str]{
+        return AssetSaver::extensions(self);
+    }
+
+    fn asset_type_uuid(&self)->Uuid{
+        return <S::Asset as bevy::reflect::TypeUuid>::TYPE_UUID;
+    }
+}
@@ -0,0 +1,72 @@
+//! An app with the asset plugin reading from a `MemoryAssetIo`, and a text asset to load.
+
+use super::asset_server::{AssetServer,register_asset,add_loader};
+use super::asset_memory::{AssetMemory,AssetMemoryUsage};
+use super::error::{AssetLoadError};
+use super::handle::{HandleId};
+use super::io::{MemoryAssetIo};
+use super::load_context::{LoadContext};
+use super::load_state::{LoadState};
+use super::loader::{AssetLoader};
+use super::plugin::{AssetPlugin};
+use bevy::prelude::{App};
+use bevy::reflect::TypeUuid;
+use std::time::{Duration};
+
+#[derive(TypeUuid,Debug,PartialEq)]
+#[uuid = "3c4e1f5a-8d52-4f0e-9a3b-7c1d2e6f9b10"]
+pub(crate) struct TextAsset(pub String);
+
+impl AssetMemoryUsage for TextAsset{
+    fn memory_usage(&self)->AssetMemory{
+        AssetMemory{cpu_bytes:self.0.len(),gpu_bytes:0}
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct TextLoader;
+
+impl AssetLoader for TextLoader{
+    type Asset = TextAsset;
+    type Settings = ();
+
+    fn load(&self,bytes:&[u8],_settings:&(),load_context:&mut LoadContext)->Result<TextAsset,AssetLoadError>{
+        let text = std::str::from_utf8(bytes).map_err(|e|AssetLoadError::Decode{
+            path:load_context.path().clone(),
+            message:e.to_string(),
+        })?;
+        return Ok(TextAsset(text.to_string()));
+    }
+
+    fn extensions(&self)->&[&str]{
+        return &["txt"];
+    }
+}
+
+/// `io` serves `res://`, keep a clone of it to add files later.
+pub(crate) fn test_app(io: MemoryAssetIo)->App{
+    let mut app = App::new();
+    app.insert_resource(AssetServer::new(Box::new(io)));
+    app.add_plugin(AssetPlugin{});
+    register_asset::<TextAsset>(&mut app);
+    add_loader(&mut app,TextLoader);
+    return app;
+}
+
+pub(crate) fn asset_server(app: &mut App)->bevy::prelude::Mut<AssetServer>{
+    return app.world.get_resource_mut::<AssetServer>().unwrap();
+}
+
+/// Runs frames until the asset left `LoadState::Loading`, returns the state it ended in.
+pub(crate) fn update_until_loaded(app: &mut App,id: HandleId)->LoadState{
+    for _ in 0..1000{
+        app.update();
+        let state = app.world.get_resource::<AssetServer>().unwrap().get_load_state(id);
+        if state != LoadState::Loading{
+            return state;
+        }
+        // the loader pool runs on other threads
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    panic!("{:?} is still loading",id);
+}
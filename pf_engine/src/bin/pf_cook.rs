@@ -0,0 +1,36 @@
+//! Cooks source assets into engine formats, see `pf_engine::asset_processor`.
+//!
+//! `pf_cook <source dir> <output dir>`
+
+use pf_engine::asset_processor::AssetProcessor;
+use std::process::exit;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.len() != 2 {
+        eprintln!("usage: pf_cook <source dir> <output dir>");
+        exit(2);
+    }
+
+    let report = match AssetProcessor::new(&args[0], &args[1]).process() {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    };
+    for (source, error) in report.failed.iter() {
+        eprintln!("failed to cook {}: {}", source, error);
+    }
+    println!(
+        "{} cooked, {} up to date, {} copied, {} removed, {} failed",
+        report.cooked.len(),
+        report.skipped.len(),
+        report.copied.len(),
+        report.removed.len(),
+        report.failed.len()
+    );
+    if !report.failed.is_empty() {
+        exit(1);
+    }
+}
@@ -0,0 +1,35 @@
+//! Builds an asset pack from a directory.
+//!
+//! `pf_pack <asset dir> <output file> [--compress]`
+
+use pf_engine::asset_server::pack::{AssetPackBuilder,PackCompression};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::process::exit;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let compress = args.iter().any(|arg| arg == "--compress");
+    let paths: Vec<&String> = args.iter().filter(|arg| !arg.starts_with("--")).collect();
+    if paths.len() != 2 {
+        eprintln!("usage: pf_pack <asset dir> <output file> [--compress]");
+        exit(2);
+    }
+
+    let compression = if compress { PackCompression::Deflate } else { PackCompression::None };
+    let mut builder = AssetPackBuilder::new().with_compression(compression);
+    if let Err(e) = builder.add_dir(paths[0]) {
+        eprintln!("failed to collect {}: {}", paths[0], e);
+        exit(1);
+    }
+    // flushed explicitly, dropping the writer would swallow a failed final write
+    let result = File::create(paths[1]).and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        builder.write_to(&mut writer)?;
+        writer.flush()
+    });
+    if let Err(e) = result {
+        eprintln!("failed to write {}: {}", paths[1], e);
+        exit(1);
+    }
+}
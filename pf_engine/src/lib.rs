@@ -9,7 +9,7 @@ pub use rg3d_core as core;
 pub mod utils;
 pub mod render;
 pub mod asset_server;
-pub mod fs;
+pub mod asset_processor;
 
 pub use render::material_mesh::{MaterialMeshBundle};
 pub use render::material::{Material};
@@ -6,4 +6,4 @@ pub fn init_android_logger(tag: &str) {
     );
 }
 
-pub use log::{info,error};
+pub use log::{info,warn,error};
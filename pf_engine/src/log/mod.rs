@@ -3,9 +3,9 @@ pub mod android_log;
 #[cfg(target_os="android")]
 pub use android_log::*;
 
-#[cfg(target_os="windows")]
+#[cfg(not(target_os="android"))]
 pub mod windows_log;
-#[cfg(target_os="windows")]
+#[cfg(not(target_os="android"))]
 pub use windows_log::*; 
 
 
@@ -1 +1 @@
-pub use log::{info,error};
+pub use log::{info,warn,error};
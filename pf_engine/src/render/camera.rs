@@ -0,0 +1,36 @@
+use super::render_target::{RenderTarget};
+use crate::asset_server::handle::{Handle};
+use bevy::ecs::component::Component;
+
+/// Draws the meshes of the world into the window or a `RenderTarget`. Without any camera the
+/// renderer draws into the window as if there was a default one.
+#[derive(Component)]
+pub struct Camera {
+    /// `None` draws into the window.
+    pub target: Option<Handle<RenderTarget>>,
+    pub clear_color: [f32; 4],
+    /// Cameras draw in ascending order, so one drawing into a render target goes before the
+    /// cameras whose materials sample it.
+    pub order: i32,
+}
+
+impl Camera {
+    pub fn with_target(target: Handle<RenderTarget>)->Self{
+        Self{
+            target: Some(target),
+            // drawn before the window by default
+            order: -1,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for Camera {
+    fn default()->Self{
+        Self{
+            target: None,
+            clear_color: [0.1, 0.2, 0.3, 0.5],
+            order: 0,
+        }
+    }
+}
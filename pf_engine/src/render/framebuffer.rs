@@ -0,0 +1,106 @@
+use crate::core::math::Rect;
+use crate::render::state::{DeletionQueue,GlObject,PipelineState};
+use super::{FrameworkError,GPUTexture,GpuTextureKind};
+use glow::HasContext;
+
+#[derive(Copy,Clone,PartialEq,Eq,Debug)]
+pub enum AttachmentKind{
+    Color,
+    DepthStencil,
+    Depth,
+}
+
+/// A texture drawn into by a `FrameBuffer`. The frame buffer does not own it, the texture has
+/// to outlive every draw into the frame buffer.
+pub struct Attachment<'a>{
+    pub kind: AttachmentKind,
+    pub texture: &'a GPUTexture,
+}
+
+/// A set of 2D textures drawn into instead of the window.
+#[derive(Debug)]
+pub struct FrameBuffer{
+    framebuffer: glow::Framebuffer,
+    width: usize,
+    height: usize,
+    deletions: DeletionQueue,
+}
+
+impl FrameBuffer{
+    /// Every attachment has to be a `GpuTextureKind::Rectangle` of the same size, color ones
+    /// are drawn to in the order given. Fails with `FrameworkError::InvalidFrameBuffer` when
+    /// the attachments do not fit together or the driver reports the frame buffer incomplete.
+    pub fn new(state: &mut PipelineState,depth_attachment: Option<Attachment>,color_attachments: &[Attachment])->Result<Self,FrameworkError>{
+        let mut size = None;
+        for attachment in depth_attachment.iter().chain(color_attachments.iter()){
+            let attachment_size = match attachment.texture.kind{
+                GpuTextureKind::Rectangle{width,height}=>(width,height),
+                _=>return Err(FrameworkError::InvalidFrameBuffer),
+            };
+            if *size.get_or_insert(attachment_size) != attachment_size{
+                return Err(FrameworkError::InvalidFrameBuffer);
+            }
+        }
+        let (width,height) = size.ok_or(FrameworkError::InvalidFrameBuffer)?;
+        if depth_attachment.as_ref().map_or(false,|attachment|attachment.kind == AttachmentKind::Color)
+            || color_attachments.iter().any(|attachment|attachment.kind != AttachmentKind::Color){
+            return Err(FrameworkError::InvalidFrameBuffer);
+        }
+
+        unsafe{
+            let framebuffer = state.gl.create_framebuffer().map_err(|_|FrameworkError::FailedToConstructFBO)?;
+            // queued for deletion when construction fails below
+            let result = Self{framebuffer,width,height,deletions:state.deletions()};
+
+            let previous = state.framebuffer();
+            state.set_framebuffer(Some(framebuffer));
+
+            if let Some(depth_attachment) = depth_attachment{
+                let gl_attachment = match depth_attachment.kind{
+                    AttachmentKind::DepthStencil=>glow::DEPTH_STENCIL_ATTACHMENT,
+                    _=>glow::DEPTH_ATTACHMENT,
+                };
+                state.gl.framebuffer_texture_2d(glow::FRAMEBUFFER,gl_attachment,glow::TEXTURE_2D,Some(depth_attachment.texture.texture),0);
+            }
+
+            let mut draw_buffers = Vec::with_capacity(color_attachments.len());
+            for (index,color_attachment) in color_attachments.iter().enumerate(){
+                let gl_attachment = glow::COLOR_ATTACHMENT0 + index as u32;
+                state.gl.framebuffer_texture_2d(glow::FRAMEBUFFER,gl_attachment,glow::TEXTURE_2D,Some(color_attachment.texture.texture),0);
+                draw_buffers.push(gl_attachment);
+            }
+            if draw_buffers.is_empty(){
+                draw_buffers.push(glow::NONE);
+            }
+            state.gl.draw_buffers(&draw_buffers);
+
+            let status = state.gl.check_framebuffer_status(glow::FRAMEBUFFER);
+            state.set_framebuffer(previous);
+            if status != glow::FRAMEBUFFER_COMPLETE{
+                return Err(FrameworkError::InvalidFrameBuffer);
+            }
+            return Ok(result);
+        }
+    }
+
+    /// Draws go into the frame buffer, over all of it.
+    pub fn bind(&self,state: &mut PipelineState){
+        state.set_framebuffer(Some(self.framebuffer));
+        state.set_viewport(Rect::new(0,0,self.width as i32,self.height as i32));
+    }
+
+    pub fn width(&self)->usize{
+        self.width
+    }
+
+    pub fn height(&self)->usize{
+        self.height
+    }
+}
+
+/// The frame buffer is deleted by `PipelineState::delete_dropped_objects`, on the GL thread.
+impl Drop for FrameBuffer{
+    fn drop(&mut self){
+        self.deletions.push(GlObject::FrameBuffer(self.framebuffer));
+    }
+}
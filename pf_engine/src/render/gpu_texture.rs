@@ -1,4 +1,5 @@
-use crate::render::state::PipelineState;
+use crate::render::state::{PipelineState,DeletionQueue,GlObject};
+use super::texture_binding::{texture_size_bytes};
 use super::{PixelKind,MinificationFilter,MagnificationFilter,FrameworkError,WrapMode,TextureBinding,TextureKind};
 use glow::HasContext;
 
@@ -13,8 +14,10 @@ pub struct GPUTexture{
     pub(crate)s_wrap_mode: WrapMode,
     pub(crate)t_wrap_mode: WrapMode,
     pub(crate)r_wrap_mode: WrapMode,
-    pub(crate)anisotropy: f32, // TODO 
+    pub(crate)anisotropy: f32,
     pub(crate)pixel_kind: PixelKind,
+    pub(crate)mip_count: usize,
+    deletions: DeletionQueue,
     // Force compiler to not implement Send and Sync, because OpenGL is not thread-safe.
     //thread_mark: PhantomData<*const u8>,
 }
@@ -32,6 +35,11 @@ pub enum GpuTextureKind {
         width: usize,
         height: usize,
     },
+    RectangleArray {
+        width: usize,
+        height: usize,
+        layers: usize,
+    },
     Volume {
         width: usize,
         height: usize,
@@ -53,6 +61,15 @@ impl From<TextureKind> for GpuTextureKind {
                 width: width as usize,
                 height: height as usize,
             },
+            TextureKind::RectangleArray {
+                width,
+                height,
+                layers,
+            } => GpuTextureKind::RectangleArray {
+                width: width as usize,
+                height: height as usize,
+                layers: layers as usize,
+            },
             TextureKind::Volume {
                 width,
                 height,
@@ -73,6 +90,7 @@ impl GpuTextureKind {
             Self::Line { .. } => glow::TEXTURE_1D,
             Self::Rectangle { .. } => glow::TEXTURE_2D,
             Self::Cube { .. } => glow::TEXTURE_CUBE_MAP,
+            Self::RectangleArray { .. } => glow::TEXTURE_2D_ARRAY,
             Self::Volume { .. } => glow::TEXTURE_3D,
         }
     }
@@ -106,6 +124,8 @@ impl GPUTexture {
                 r_wrap_mode: WrapMode::Repeat,
                 anisotropy: 1.0,
                 pixel_kind,
+                mip_count,
+                deletions: state.deletions(),
                 //thread_mark: PhantomData,
             };
 
@@ -137,6 +157,11 @@ impl GPUTexture {
         }
     }
 
+    /// Bytes of every mip level.
+    pub fn size_bytes(&self)->usize{
+        texture_size_bytes(self.kind,self.pixel_kind,self.mip_count)
+    }
+
     pub fn bind(&self, state: &mut PipelineState, sampler_index: u32) {
         state.set_texture(
             sampler_index,
@@ -178,4 +203,9 @@ impl GPUTexture {
     }
 }
 
-
+/// The texture is deleted by `PipelineState::delete_dropped_objects`, on the GL thread.
+impl Drop for GPUTexture{
+    fn drop(&mut self){
+        self.deletions.push(GlObject::Texture(self.texture));
+    }
+}
@@ -21,6 +21,13 @@ unsafe impl Sync for Mesh{}
 
 
 impl Mesh {
+    pub fn new(surface: SurfaceData)->Self {
+        return Mesh{
+            surface,
+            geometry_buffer:None,
+        }
+    }
+
     pub fn cube()->Self {
         return Mesh{
             surface: SurfaceData::make_cube(Matrix4::identity()),
@@ -0,0 +1,142 @@
+//! Cooked `.mesh` files, written by the asset processor and read by `MeshAssetLoader`.
+//!
+//! Layout, all integers little endian:
+//! ```text
+//! magic             b"PFMS"
+//! version           u32
+//! vertex count      u32
+//! attribute count   u8
+//! attributes        usage u8, data type u8, size u8, divisor u8, shader location u8
+//! vertex data       length u64, then the interleaved vertices
+//! triangle count    u32
+//! triangles         3 x u32 each
+//! ```
+
+use crate::systems::surface::surface::SurfaceData;
+use crate::systems::surface::buffer::{VertexBuffer,TriangleBuffer,VertexAttributeDescriptor,VertexAttributeUsage,VertexAttributeDataType};
+use crate::core::math::TriangleDefinition;
+use crate::core::byteorder::{LittleEndian,ReadBytesExt,WriteBytesExt};
+use std::io::{self,Cursor,Read,Write};
+
+pub const MESH_MAGIC: &[u8; 4] = b"PFMS";
+pub const MESH_VERSION: u32 = 1;
+
+pub fn write_cooked_mesh<W: Write>(data: &SurfaceData,writer: &mut W)->io::Result<()>{
+    writer.write_all(MESH_MAGIC)?;
+    writer.write_u32::<LittleEndian>(MESH_VERSION)?;
+    writer.write_u32::<LittleEndian>(data.vertex_buffer.vertex_count())?;
+    let layout = data.vertex_buffer.layout();
+    writer.write_u8(layout.len() as u8)?;
+    for attribute in layout{
+        writer.write_u8(attribute.usage as u8)?;
+        writer.write_u8(attribute.data_type as u8)?;
+        writer.write_u8(attribute.size)?;
+        writer.write_u8(attribute.divisor)?;
+        writer.write_u8(attribute.shader_location)?;
+    }
+    let vertex_data = data.vertex_buffer.raw_data();
+    writer.write_u64::<LittleEndian>(vertex_data.len() as u64)?;
+    writer.write_all(vertex_data)?;
+    writer.write_u32::<LittleEndian>(data.geometry_buffer.len() as u32)?;
+    for triangle in data.geometry_buffer.iter(){
+        for &index in triangle.0.iter(){
+            writer.write_u32::<LittleEndian>(index)?;
+        }
+    }
+    return Ok(());
+}
+
+pub fn read_cooked_mesh(bytes: &[u8])->Result<SurfaceData,String>{
+    let mut cursor = Cursor::new(bytes);
+    return read_mesh(&mut cursor).map_err(|e|e.to_string());
+}
+
+fn read_mesh(cursor: &mut Cursor<&[u8]>)->io::Result<SurfaceData>{
+    let mut magic = [0u8;4];
+    cursor.read_exact(&mut magic)?;
+    if &magic != MESH_MAGIC{
+        return Err(invalid_data("not a cooked mesh".to_string()));
+    }
+    let version = cursor.read_u32::<LittleEndian>()?;
+    if version != MESH_VERSION{
+        return Err(invalid_data(format!("unsupported cooked mesh version {}",version)));
+    }
+    let vertex_count = cursor.read_u32::<LittleEndian>()? as usize;
+    let attribute_count = cursor.read_u8()?;
+    let mut layout = Vec::with_capacity(attribute_count as usize);
+    for _ in 0..attribute_count{
+        layout.push(VertexAttributeDescriptor{
+            usage: usage_from_id(cursor.read_u8()?)?,
+            data_type: data_type_from_id(cursor.read_u8()?)?,
+            size: cursor.read_u8()?,
+            divisor: cursor.read_u8()?,
+            shader_location: cursor.read_u8()?,
+        });
+    }
+    let vertex_data = read_bytes(cursor)?;
+    let vertex_buffer = VertexBuffer::new(vertex_count,&layout,vertex_data)
+        .map_err(|e|invalid_data(e.to_string()))?;
+
+    let triangle_count = cursor.read_u32::<LittleEndian>()? as usize;
+    if triangle_count.saturating_mul(12) > bytes_left(cursor){
+        return Err(invalid_data("triangle data is truncated".to_string()));
+    }
+    let mut triangles = Vec::with_capacity(triangle_count);
+    for _ in 0..triangle_count{
+        let a = cursor.read_u32::<LittleEndian>()?;
+        let b = cursor.read_u32::<LittleEndian>()?;
+        let c = cursor.read_u32::<LittleEndian>()?;
+        if [a,b,c].iter().any(|&index|index as usize >= vertex_count){
+            return Err(invalid_data("triangle index out of bounds".to_string()));
+        }
+        triangles.push(TriangleDefinition([a,b,c]));
+    }
+    return Ok(SurfaceData::new(vertex_buffer,TriangleBuffer::new(triangles)));
+}
+
+fn read_bytes(cursor: &mut Cursor<&[u8]>)->io::Result<Vec<u8>>{
+    let len = cursor.read_u64::<LittleEndian>()? as usize;
+    if len > bytes_left(cursor){
+        return Err(invalid_data("vertex data is truncated".to_string()));
+    }
+    let mut bytes = vec![0u8;len];
+    cursor.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn bytes_left(cursor: &Cursor<&[u8]>)->usize{
+    cursor.get_ref().len().saturating_sub(cursor.position() as usize)
+}
+
+fn invalid_data(message: String)->io::Error{
+    io::Error::new(io::ErrorKind::InvalidData,message)
+}
+
+fn usage_from_id(id: u8)->io::Result<VertexAttributeUsage>{
+    match id{
+        0=>Ok(VertexAttributeUsage::Position),
+        1=>Ok(VertexAttributeUsage::Normal),
+        2=>Ok(VertexAttributeUsage::Tangent),
+        3=>Ok(VertexAttributeUsage::TexCoord0),
+        4=>Ok(VertexAttributeUsage::TexCoord1),
+        5=>Ok(VertexAttributeUsage::TexCoord2),
+        6=>Ok(VertexAttributeUsage::TexCoord3),
+        7=>Ok(VertexAttributeUsage::TexCoord4),
+        8=>Ok(VertexAttributeUsage::TexCoord5),
+        9=>Ok(VertexAttributeUsage::TexCoord6),
+        10=>Ok(VertexAttributeUsage::TexCoord7),
+        11=>Ok(VertexAttributeUsage::BoneWeight),
+        12=>Ok(VertexAttributeUsage::BoneIndices),
+        _=>Err(invalid_data(format!("invalid vertex attribute usage {}",id))),
+    }
+}
+
+fn data_type_from_id(id: u8)->io::Result<VertexAttributeDataType>{
+    match id{
+        0=>Ok(VertexAttributeDataType::F32),
+        1=>Ok(VertexAttributeDataType::U32),
+        2=>Ok(VertexAttributeDataType::U16),
+        3=>Ok(VertexAttributeDataType::U8),
+        _=>Err(invalid_data(format!("invalid vertex attribute data type {}",id))),
+    }
+}
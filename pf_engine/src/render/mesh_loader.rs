@@ -0,0 +1,32 @@
+use crate::asset_server::loader::AssetLoader;
+use crate::asset_server::load_context::{LoadContext};
+use crate::systems::surface::surface::SurfaceData;
+use crate::asset_server::error::{AssetLoadError};
+use super::mesh_format::{read_cooked_mesh,MESH_MAGIC};
+
+/// Loads cooked `.mesh` files into `SurfaceData`.
+pub struct MeshAssetLoader{
+
+}
+
+impl Default for MeshAssetLoader{
+    fn default()->Self{
+        Self{}
+    }
+}
+
+impl AssetLoader for MeshAssetLoader{
+    type Asset = SurfaceData;
+    type Settings = ();
+
+    fn extensions(&self)->&[&str]{
+        return &["mesh"];
+    }
+    fn sniff(&self,bytes: &[u8])->bool{
+        return bytes.starts_with(MESH_MAGIC);
+    }
+    fn load(&self,bytes: &[u8],_settings: &(),load_context: &mut LoadContext)->Result<SurfaceData,AssetLoadError>{
+        return read_cooked_mesh(bytes)
+            .map_err(|message|AssetLoadError::Decode{path:load_context.path().clone(),message});
+    }
+}
@@ -0,0 +1,70 @@
+use crate::asset_server::saver::AssetSaver;
+use crate::asset_server::asset_path::{AssetPath};
+use crate::asset_server::error::{AssetSaveError};
+use crate::systems::surface::surface::SurfaceData;
+use super::mesh_format::{write_cooked_mesh};
+
+/// Writes `SurfaceData` as cooked `.mesh` files, which `MeshAssetLoader` reads back.
+#[derive(Default)]
+pub struct MeshAssetSaver{}
+
+impl AssetSaver for MeshAssetSaver{
+    type Asset = SurfaceData;
+
+    fn extensions(&self)->&[&str]{
+        return &["mesh"];
+    }
+    fn save(&self,data: &SurfaceData,asset_path: &AssetPath)->Result<Vec<u8>,AssetSaveError>{
+        let mut bytes = Vec::new();
+        write_cooked_mesh(data,&mut bytes)
+            .map_err(|e|AssetSaveError::Encode{path:asset_path.clone(),message:e.to_string()})?;
+        return Ok(bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::asset_server::asset_server::{AssetServer,register_asset,add_loader,add_saver};
+    use crate::asset_server::assets::{Assets};
+    use crate::asset_server::io::{MemoryAssetIo};
+    use crate::asset_server::load_state::{LoadState};
+    use crate::asset_server::test_util::{test_app,asset_server,update_until_loaded};
+    use super::super::mesh_loader::{MeshAssetLoader};
+
+    fn triangles(data: &SurfaceData)->Vec<[u32;3]>{
+        return data.geometry_buffer.iter().map(|triangle|triangle.0).collect();
+    }
+
+    #[test]
+    fn saved_mesh_loads_back_unchanged(){
+        let mut app = test_app(MemoryAssetIo::default());
+        register_asset::<SurfaceData>(&mut app);
+        add_loader(&mut app,MeshAssetLoader::default());
+        add_saver(&mut app,MeshAssetSaver::default());
+
+        let handle = app.world.get_resource_mut::<Assets<SurfaceData>>().unwrap()
+            .add(SurfaceData::make_unit_xy_quad());
+        let assets = app.world.get_resource::<Assets<SurfaceData>>().unwrap();
+        app.world.get_resource::<AssetServer>().unwrap().save(assets,&handle,"saved/quad.mesh").unwrap();
+
+        let loaded = asset_server(&mut app).load::<SurfaceData,_>("saved/quad.mesh");
+        assert_eq!(update_until_loaded(&mut app,loaded.id()),LoadState::Loaded);
+        let assets = app.world.get_resource::<Assets<SurfaceData>>().unwrap();
+        let loaded = assets.get_asset(&loaded).unwrap();
+        let saved = SurfaceData::make_unit_xy_quad();
+        assert_eq!(loaded.vertex_buffer.vertex_count(),saved.vertex_buffer.vertex_count());
+        assert_eq!(loaded.vertex_buffer.vertex_size(),saved.vertex_buffer.vertex_size());
+        assert_eq!(loaded.vertex_buffer.layout().len(),saved.vertex_buffer.layout().len());
+        for (loaded,saved) in loaded.vertex_buffer.layout().iter().zip(saved.vertex_buffer.layout()){
+            assert_eq!(loaded.usage,saved.usage);
+            assert_eq!(loaded.data_type,saved.data_type);
+            assert_eq!(loaded.size,saved.size);
+            assert_eq!(loaded.divisor,saved.divisor);
+            assert_eq!(loaded.offset,saved.offset);
+            assert_eq!(loaded.shader_location,saved.shader_location);
+        }
+        assert_eq!(loaded.vertex_buffer.raw_data(),saved.vertex_buffer.raw_data());
+        assert_eq!(triangles(loaded),triangles(&saved));
+    }
+}
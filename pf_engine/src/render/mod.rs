@@ -8,20 +8,35 @@ pub mod material;
 pub mod gpu_texture;
 pub mod texture;
 pub mod texture_loader;
+pub mod texture_saver;
+pub mod texture_format;
+pub mod texture_import;
+pub mod texture_container;
+pub mod texture_decompress;
+pub mod texture_mips;
+pub mod mesh_format;
+pub mod mesh_loader;
+pub mod mesh_saver;
 pub mod plugin;
 pub mod material_mesh;
 pub mod pixel_kind;
 pub mod texture_property;
 pub mod texture_binding;
+pub mod framebuffer;
+pub mod render_target;
+pub mod camera;
 
 pub use mesh::{Mesh};
 pub use material::{Material};
 pub use pixel_kind::PixelKind;
-pub use texture_property::{MinificationFilter,MagnificationFilter,WrapMode};
+pub use texture_property::{MinificationFilter,MagnificationFilter,WrapMode,Coordinate};
 pub use error::{FrameworkError};
 pub use texture_binding::TextureBinding;
-pub use state::PipelineState;
+pub use state::{PipelineState,TextureCompressionSupport};
 pub use gpu_texture::{GPUTexture,GpuTextureKind};
-pub use texture::{TextureKind,TextureMinificationFilter,TextureMagnificationFilter,Texture};
+pub use texture::{TextureKind,TextureMinificationFilter,TextureMagnificationFilter,TextureWrapMode,Texture};
 pub use material_mesh::{MaterialMeshBundle};
+pub use framebuffer::{FrameBuffer,Attachment,AttachmentKind};
+pub use render_target::{RenderTarget};
+pub use camera::{Camera};
 
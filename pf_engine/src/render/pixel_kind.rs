@@ -10,6 +10,7 @@ pub enum PixelKind {
     RGB8,
     SRGB8,
     BGRA8,
+    SBGRA8,
     BGR8,
     RG8,
     RG16,
@@ -22,6 +23,10 @@ pub enum PixelKind {
     DXT1RGBA,
     DXT3RGBA,
     DXT5RGBA,
+    DXT1SRGB,
+    DXT1SRGBA,
+    DXT3SRGBA,
+    DXT5SRGBA,
     RGB32F,
     RGBA32F,
     RGBA16F,
@@ -29,6 +34,22 @@ pub enum PixelKind {
     RG8RGTC,
     R11G11B10F,
     RGB10A2,
+    ETC2RGB8,
+    ETC2SRGB8,
+    ETC2RGB8A1,
+    ETC2SRGB8A1,
+    ETC2RGBA8,
+    ETC2SRGBA8,
+    EACR11,
+    EACRG11,
+    ASTC4x4,
+    ASTC4x4SRGB,
+    ASTC5x5,
+    ASTC5x5SRGB,
+    ASTC6x6,
+    ASTC6x6SRGB,
+    ASTC8x8,
+    ASTC8x8SRGB,
 }
 
 impl PixelKind{
@@ -38,8 +59,28 @@ impl PixelKind{
             | Self::DXT1RGBA
             | Self::DXT3RGBA
             | Self::DXT5RGBA
+            | Self::DXT1SRGB
+            | Self::DXT1SRGBA
+            | Self::DXT3SRGBA
+            | Self::DXT5SRGBA
             | Self::R8RGTC
-            | Self::RG8RGTC => true,
+            | Self::RG8RGTC
+            | Self::ETC2RGB8
+            | Self::ETC2SRGB8
+            | Self::ETC2RGB8A1
+            | Self::ETC2SRGB8A1
+            | Self::ETC2RGBA8
+            | Self::ETC2SRGBA8
+            | Self::EACR11
+            | Self::EACRG11
+            | Self::ASTC4x4
+            | Self::ASTC4x4SRGB
+            | Self::ASTC5x5
+            | Self::ASTC5x5SRGB
+            | Self::ASTC6x6
+            | Self::ASTC6x6SRGB
+            | Self::ASTC8x8
+            | Self::ASTC8x8SRGB => true,
             // Explicit match for rest of formats instead of _ will help to not forget
             // to add new entry here.
             Self::RGBA16
@@ -50,6 +91,7 @@ impl PixelKind{
             | Self::RGB8
             | Self::SRGB8
             | Self::BGRA8
+            | Self::SBGRA8
             | Self::BGR8
             | Self::RG16
             | Self::R16
@@ -76,6 +118,7 @@ impl PixelKind{
             | Self::RGB8
             | Self::SRGB8
             | Self::BGRA8
+            | Self::SBGRA8
             | Self::BGR8
             | Self::RG16
             | Self::R16
@@ -90,8 +133,28 @@ impl PixelKind{
             | Self::DXT1RGBA
             | Self::DXT3RGBA
             | Self::DXT5RGBA
+            | Self::DXT1SRGB
+            | Self::DXT1SRGBA
+            | Self::DXT3SRGBA
+            | Self::DXT5SRGBA
             | Self::R8RGTC
-            | Self::RG8RGTC => None,
+            | Self::RG8RGTC
+            | Self::ETC2RGB8
+            | Self::ETC2SRGB8
+            | Self::ETC2RGB8A1
+            | Self::ETC2SRGB8A1
+            | Self::ETC2RGBA8
+            | Self::ETC2SRGBA8
+            | Self::EACR11
+            | Self::EACRG11
+            | Self::ASTC4x4
+            | Self::ASTC4x4SRGB
+            | Self::ASTC5x5
+            | Self::ASTC5x5SRGB
+            | Self::ASTC6x6
+            | Self::ASTC6x6SRGB
+            | Self::ASTC8x8
+            | Self::ASTC8x8SRGB => None,
         }
     }
 }
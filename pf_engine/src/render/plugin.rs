@@ -1,13 +1,23 @@
 use bevy::prelude::{Plugin,App};
-use crate::asset_server::asset_server::{register_asset,add_loader};
+use crate::asset_server::asset_server::{register_asset,add_loader,add_saver};
 use super::texture_loader::TextureAssetLoader;
 use super::texture::{Texture};
+use super::render_target::{RenderTarget};
+use super::mesh_loader::MeshAssetLoader;
+use super::texture_saver::TextureAssetSaver;
+use super::mesh_saver::MeshAssetSaver;
+use crate::systems::surface::surface::SurfaceData;
 pub struct RendererAssetPlugin{}
 
 impl Plugin for RendererAssetPlugin{
     fn build(&self,app: &mut App){
         //let mut asset_server = app.world.get_resource_mut::<AssetServer>().unwrap();
         register_asset::<Texture>(app);
-        add_loader(app,Box::new(TextureAssetLoader::default()));
+        add_loader(app,TextureAssetLoader::default());
+        add_saver(app,TextureAssetSaver::default());
+        register_asset::<RenderTarget>(app);
+        register_asset::<SurfaceData>(app);
+        add_loader(app,MeshAssetLoader::default());
+        add_saver(app,MeshAssetSaver::default());
     }
 }
@@ -0,0 +1,109 @@
+use super::framebuffer::{Attachment,AttachmentKind,FrameBuffer};
+use super::texture::{Texture,TextureData,TextureKind,TexturePixelKind,TextureWrapMode};
+use super::{GPUTexture,GpuTextureKind,PixelKind,PipelineState,FrameworkError,MinificationFilter,MagnificationFilter};
+use crate::asset_server::asset_memory::{AssetMemory,AssetMemoryUsage};
+use crate::asset_server::handle::{Handle};
+use crate::asset_server::assets::{Assets};
+use bevy::reflect::TypeUuid;
+use std::path::PathBuf;
+
+/// A texture cameras draw into, see `Camera::target`. Materials sample the drawing through
+/// `color_texture`, like any other texture.
+#[derive(TypeUuid,Debug)]
+#[uuid = "b394b26a-2d0b-4be4-b2ec-54ce53eafccd"]
+pub struct RenderTarget{
+    width: u32,
+    height: u32,
+    color_texture: Handle<Texture>,
+    depth_stencil_texture: Option<GPUTexture>,
+    frame_buffer: Option<FrameBuffer>,
+    // the color texture the frame buffer was built with, uploading it again replaces it
+    attached_color_texture: Option<glow::Texture>,
+}
+
+impl RenderTarget{
+    /// Adds the RGBA8 color texture to `textures`. The GL objects are made on first draw.
+    pub fn new(width: u32,height: u32,textures: &mut Assets<Texture>)->Self{
+        let mut data = TextureData::new(PathBuf::new(),Vec::new());
+        data.kind = TextureKind::Rectangle{width,height};
+        data.pixel_kind = TexturePixelKind::RGBA8;
+        data.s_wrap_mode = TextureWrapMode::ClampToEdge;
+        data.t_wrap_mode = TextureWrapMode::ClampToEdge;
+        data.is_render_target = true;
+        return Self{
+            width,
+            height,
+            color_texture: textures.add(Texture::from_texture_data(data)),
+            depth_stencil_texture: None,
+            frame_buffer: None,
+            attached_color_texture: None,
+        };
+    }
+
+    pub fn width(&self)->u32{
+        self.width
+    }
+
+    pub fn height(&self)->u32{
+        self.height
+    }
+
+    pub fn color_texture(&self)->&Handle<Texture>{
+        &self.color_texture
+    }
+
+    /// What was drawn so far is lost.
+    pub fn resize(&mut self,width: u32,height: u32,textures: &mut Assets<Texture>){
+        self.width = width;
+        self.height = height;
+        if let Some(data) = textures.get_asset_mut(&self.color_texture).and_then(|texture|texture.data_mut()){
+            data.kind = TextureKind::Rectangle{width,height};
+        }
+        self.depth_stencil_texture = None;
+        self.frame_buffer = None;
+    }
+
+    /// Uploads the color texture and builds the frame buffer when either is missing.
+    pub(crate) fn frame_buffer(&mut self,state: &mut PipelineState,textures: &mut Assets<Texture>)->Result<&FrameBuffer,FrameworkError>{
+        let color_texture = textures.get_asset_mut(&self.color_texture)
+            .ok_or_else(||FrameworkError::Custom("the color texture of a render target is gone".to_string()))?
+            .upload(state)?;
+        if self.frame_buffer.is_none() || self.attached_color_texture != Some(color_texture.texture){
+            if self.depth_stencil_texture.is_none(){
+                self.depth_stencil_texture = Some(GPUTexture::new(
+                    state,
+                    GpuTextureKind::Rectangle{width:self.width as usize,height:self.height as usize},
+                    PixelKind::D24S8,
+                    MinificationFilter::Nearest,
+                    MagnificationFilter::Nearest,
+                    1,
+                    None,
+                    )?);
+            }
+            let depth_stencil = Attachment{
+                kind: AttachmentKind::DepthStencil,
+                texture: self.depth_stencil_texture.as_ref().unwrap(),
+            };
+            let color = Attachment{
+                kind: AttachmentKind::Color,
+                texture: color_texture,
+            };
+            self.frame_buffer = Some(FrameBuffer::new(state,Some(depth_stencil),&[color])?);
+            self.attached_color_texture = Some(color_texture.texture);
+        }
+        return Ok(self.frame_buffer.as_ref().unwrap());
+    }
+}
+
+impl AssetMemoryUsage for RenderTarget{
+    /// The color texture is counted by its own asset.
+    fn memory_usage(&self)->AssetMemory{
+        AssetMemory{
+            cpu_bytes: 0,
+            gpu_bytes: self.depth_stencil_texture.as_ref().map_or(0,|texture|texture.size_bytes()),
+        }
+    }
+}
+
+unsafe impl Send for RenderTarget{}
+unsafe impl Sync for RenderTarget{}
@@ -1,6 +1,8 @@
-//use crate::core::{ math::Rect};
+use crate::core::math::Rect;
 
+use super::PixelKind;
 use glow::HasContext;
+use std::sync::{Arc,Mutex};
 
 
 pub struct PipelineState {
@@ -22,8 +24,8 @@ pub struct PipelineState {
     //clear_depth: f32,
     //scissor_test: bool,
 
-    //framebuffer: Option<glow::Framebuffer>,
-    //viewport: Rect<i32>,
+    framebuffer: Option<glow::Framebuffer>,
+    viewport: Rect<i32>,
 
     //blend_func: BlendFunc,
 
@@ -36,6 +38,10 @@ pub struct PipelineState {
     vao: Option<glow::VertexArray>,
     vbo: Option<glow::Buffer>,
 
+    texture_compression: TextureCompressionSupport,
+    anisotropic_filtering: bool,
+    deletions: DeletionQueue,
+
     //frame_statistics: PipelineStatistics,
 }
 
@@ -45,6 +51,14 @@ impl PipelineState{
             context.depth_func(CompareFunc::default() as u32);
         }
 
+        let texture_compression = TextureCompressionSupport::query(&context);
+        let anisotropic_filtering = {
+            let extensions = context.supported_extensions();
+            let version = context.version();
+            extensions.contains("GL_EXT_texture_filter_anisotropic") || extensions.contains("GL_ARB_texture_filter_anisotropic")
+                || (!version.is_embedded && (version.major,version.minor) >= (4,6))
+        };
+
         Self {
             gl: context,
             //blend: false,
@@ -60,18 +74,76 @@ impl PipelineState{
             //clear_stencil: 0,
             //clear_depth: 1.0,
             //scissor_test: false,
-            //framebuffer: None,
+            framebuffer: None,
             //blend_func: Default::default(),
-            //viewport: Rect::new(0, 0, 1, 1),
+            viewport: Rect::new(0, 0, 1, 1),
             program: Default::default(),
             texture_units: [Default::default(); 32],
             //stencil_func: Default::default(),
             //stencil_op: Default::default(),
             vao: Default::default(),
             vbo: Default::default(),
+            texture_compression,
+            anisotropic_filtering,
+            deletions: Default::default(),
             //frame_statistics: Default::default(),
         }
     }
+
+    pub fn texture_compression(&self)->TextureCompressionSupport{
+        self.texture_compression
+    }
+
+    pub fn supports_anisotropic_filtering(&self)->bool{
+        self.anisotropic_filtering
+    }
+
+    pub(crate) fn deletions(&self)->DeletionQueue{
+        self.deletions.clone()
+    }
+
+    /// Deletes the GL objects of the `GPUTexture`s and `FrameBuffer`s dropped since the last
+    /// call. Call it on the GL thread, once a frame.
+    pub fn delete_dropped_objects(&mut self){
+        for object in self.deletions.take(){
+            match object{
+                GlObject::Texture(texture)=>{
+                    // GL unbinds a deleted texture from every unit
+                    for unit in self.texture_units.iter_mut().filter(|unit|unit.texture == Some(texture)){
+                        unit.texture = None;
+                    }
+                    unsafe{ self.gl.delete_texture(texture); }
+                },
+                GlObject::FrameBuffer(framebuffer)=>{
+                    // and a deleted framebuffer falls back to the default one
+                    if self.framebuffer == Some(framebuffer){
+                        self.framebuffer = None;
+                    }
+                    unsafe{ self.gl.delete_framebuffer(framebuffer); }
+                },
+            }
+        }
+    }
+
+    pub fn framebuffer(&self)->Option<glow::Framebuffer>{
+        self.framebuffer
+    }
+
+    /// `None` binds the window.
+    pub fn set_framebuffer(&mut self,framebuffer: Option<glow::Framebuffer>){
+        if self.framebuffer != framebuffer{
+            self.framebuffer = framebuffer;
+            unsafe{ self.gl.bind_framebuffer(glow::FRAMEBUFFER,self.framebuffer); }
+        }
+    }
+
+    pub fn set_viewport(&mut self,viewport: Rect<i32>){
+        if self.viewport != viewport{
+            self.viewport = viewport;
+            unsafe{ self.gl.viewport(self.viewport.x(),self.viewport.y(),self.viewport.w(),self.viewport.h()); }
+        }
+    }
+
     pub fn set_vertex_buffer_object(&mut self, vbo: Option<glow::Buffer>) {
         if self.vbo != vbo {
             self.vbo = vbo;
@@ -126,6 +198,71 @@ impl PipelineState{
 
 }
 
+#[derive(Debug)]
+pub(crate) enum GlObject{
+    Texture(glow::Texture),
+    FrameBuffer(glow::Framebuffer),
+}
+
+/// GL objects of dropped `GPUTexture`s and `FrameBuffer`s. Assets may be dropped on any thread
+/// while GL calls belong to the GL thread, so they wait here for
+/// `PipelineState::delete_dropped_objects`.
+#[derive(Clone, Default, Debug)]
+pub(crate) struct DeletionQueue(Arc<Mutex<Vec<GlObject>>>);
+
+impl DeletionQueue{
+    pub(crate) fn push(&self,object: GlObject){
+        self.0.lock().unwrap().push(object);
+    }
+
+    fn take(&self)->Vec<GlObject>{
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+/// Compressed texture families the context can sample, queried once when the state is created.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct TextureCompressionSupport{
+    pub s3tc: bool,
+    /// sRGB variants of the S3TC formats.
+    pub s3tc_srgb: bool,
+    pub rgtc: bool,
+    pub etc2: bool,
+    pub astc: bool,
+}
+
+impl TextureCompressionSupport{
+    fn query(gl: &glow::Context)->Self{
+        let version = gl.version();
+        let extensions = gl.supported_extensions();
+        let has = |name: &str|extensions.contains(name);
+        let at_least = |major: u32,minor: u32|(version.major,version.minor) >= (major,minor);
+        return Self{
+            s3tc: has("GL_EXT_texture_compression_s3tc"),
+            s3tc_srgb: has("GL_EXT_texture_compression_s3tc_srgb") || (has("GL_EXT_texture_compression_s3tc") && has("GL_EXT_texture_sRGB")),
+            rgtc: (!version.is_embedded && at_least(3,0)) || has("GL_EXT_texture_compression_rgtc") || has("GL_ARB_texture_compression_rgtc"),
+            // core in GLES 3.0 and in desktop GL through ES3 compatibility
+            etc2: (version.is_embedded && at_least(3,0)) || (!version.is_embedded && at_least(4,3)) || has("GL_ARB_ES3_compatibility"),
+            astc: (version.is_embedded && at_least(3,2)) || has("GL_KHR_texture_compression_astc_ldr") || has("GL_OES_texture_compression_astc"),
+        };
+    }
+
+    /// Whether textures of `pixel_kind` can be uploaded as they are, always true for
+    /// uncompressed kinds.
+    pub fn supports(&self,pixel_kind: PixelKind)->bool{
+        match pixel_kind{
+            PixelKind::DXT1RGB|PixelKind::DXT1RGBA|PixelKind::DXT3RGBA|PixelKind::DXT5RGBA=>self.s3tc,
+            PixelKind::DXT1SRGB|PixelKind::DXT1SRGBA|PixelKind::DXT3SRGBA|PixelKind::DXT5SRGBA=>self.s3tc_srgb,
+            PixelKind::R8RGTC|PixelKind::RG8RGTC=>self.rgtc,
+            PixelKind::ETC2RGB8|PixelKind::ETC2SRGB8|PixelKind::ETC2RGB8A1|PixelKind::ETC2SRGB8A1
+                |PixelKind::ETC2RGBA8|PixelKind::ETC2SRGBA8|PixelKind::EACR11|PixelKind::EACRG11=>self.etc2,
+            PixelKind::ASTC4x4|PixelKind::ASTC4x4SRGB|PixelKind::ASTC5x5|PixelKind::ASTC5x5SRGB
+                |PixelKind::ASTC6x6|PixelKind::ASTC6x6SRGB|PixelKind::ASTC8x8|PixelKind::ASTC8x8SRGB=>self.astc,
+            _=>true,
+        }
+    }
+}
+
 #[derive(Copy, Clone, PartialOrd, PartialEq, Eq, Ord, Hash, Debug)]
 #[repr(u32)]
 pub enum CompareFunc {
@@ -1,12 +1,15 @@
 use super::gpu_texture::GPUTexture;
-use super::{PixelKind};
+use super::{PixelKind,PipelineState,FrameworkError,TextureBinding,Coordinate};
 use std::ops::Deref;
 use bevy::reflect::TypeUuid;
-use crate::log::{info};
+use super::texture_decompress::{decompress_to_rgba8};
+use crate::log::{error,warn};
+use crate::asset_server::asset_memory::{AssetMemory,AssetMemoryUsage};
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::path::PathBuf;
 use std::ops::DerefMut;
+use serde::{Deserialize,Serialize};
 
 #[derive(TypeUuid,Debug)]
 #[uuid = "39cadc56-aa9c-4543-8640-a018b74b5053"]
@@ -15,6 +18,9 @@ use std::ops::DerefMut;
 pub struct Texture {
     pub(crate)gpu_texture: Option<GPUTexture>,
     pub(crate)data: Option<TextureData>,
+    /// `data` changed since it was last uploaded.
+    needs_upload: bool,
+    release_cpu_data: bool,
 }
 
 impl Texture {
@@ -22,39 +28,134 @@ impl Texture {
         Self{
             gpu_texture: None,
             data: Some(data),
+            needs_upload: true,
+            release_cpu_data: false,
         }
     }
 
-    pub fn maybe_init_gpu_texture(&mut self){
-            self.gpu_texture.get_or_insert_with(
-                || {
-                    let texture_data = self.data.as_ref().unwrap();
-                    info!("texture_data {:?}",texture_data);
-                    todo!("");
-                    //GpuTexture::new(
-                    //    state: self.state,
-                    //    kind: texture.kind,
-                    //    pixel_kind: texture,
-                    //    min_filter: MinificationFilter,
-                    //    mag_filter: MagnificationFilter,
-                    //    mip_count: usize,
-                    //    data: Option<&[u8]>,
-                    //    )
-                }
-                );
+    /// `None` once the pixel data is released after upload, see `set_release_cpu_data`.
+    pub fn data(&self)->Option<&TextureData>{
+        self.data.as_ref()
+    }
+
+    /// The texture is uploaded again the next time it is drawn.
+    pub fn data_mut(&mut self)->Option<&mut TextureData>{
+        self.needs_upload = self.data.is_some();
+        self.data.as_mut()
+    }
+
+    pub fn set_data(&mut self,data: TextureData){
+        self.data = Some(data);
+        self.needs_upload = true;
+    }
+
+    /// Drops the pixel data once it is uploaded, the texture then can neither be changed
+    /// through `data_mut` nor saved.
+    pub fn set_release_cpu_data(&mut self,release: bool){
+        self.release_cpu_data = release;
+    }
+
+    pub fn is_uploaded(&self)->bool{
+        self.gpu_texture.is_some() && !self.needs_upload
+    }
+
+    /// Uploads the pixel data when it changed since the last upload, with the filters, wrap
+    /// modes and anisotropy it carries. The previous GPU texture is kept when this fails.
+    pub fn upload(&mut self,state: &mut PipelineState)->Result<&GPUTexture,FrameworkError>{
+        if self.needs_upload || self.gpu_texture.is_none(){
+            let data = self.data.as_ref().ok_or(FrameworkError::EmptyTextureData)?;
+            // only retry once the data changes again
+            self.needs_upload = false;
+            self.gpu_texture = Some(upload_texture_data(state,data)?);
+            if self.release_cpu_data{
+                self.data = None;
+            }
+        }
+        return Ok(self.gpu_texture.as_ref().unwrap());
+    }
+
+    /// `upload`, falling back to white when the texture has never been uploaded. Errors are
+    /// logged once per change of the data.
+    pub(crate) fn gpu_texture(&mut self,state: &mut PipelineState)->&GPUTexture{
+        if let Err(e) = self.upload(state).map(|_|()){
+            error!("failed to upload texture {:?}: {}",self.data.as_ref().map(|data|&data.path),e);
+            if self.gpu_texture.is_none(){
+                self.gpu_texture = Some(GPUTexture::white_dummy(state));
+            }
+        }
+        return self.gpu_texture.as_ref().unwrap();
+    }
+}
 
+/// Compressed pixel kinds the device cannot sample are decompressed on the CPU first.
+fn upload_texture_data(state: &mut PipelineState,data: &TextureData)->Result<GPUTexture,FrameworkError>{
+    let pixel_kind = match (data.pixel_kind,data.is_srgb){
+        (TexturePixelKind::RGB8,true)=>PixelKind::SRGB8,
+        (TexturePixelKind::RGBA8,true)=>PixelKind::SRGBA8,
+        (TexturePixelKind::BGRA8,true)=>PixelKind::SBGRA8,
+        (TexturePixelKind::DXT1RGB,true)=>PixelKind::DXT1SRGB,
+        (TexturePixelKind::DXT1RGBA,true)=>PixelKind::DXT1SRGBA,
+        (TexturePixelKind::DXT3RGBA,true)=>PixelKind::DXT3SRGBA,
+        (TexturePixelKind::DXT5RGBA,true)=>PixelKind::DXT5SRGBA,
+        (TexturePixelKind::ETC2RGB8,true)=>PixelKind::ETC2SRGB8,
+        (TexturePixelKind::ETC2RGB8A1,true)=>PixelKind::ETC2SRGB8A1,
+        (TexturePixelKind::ETC2RGBA8,true)=>PixelKind::ETC2SRGBA8,
+        (TexturePixelKind::ASTC4x4,true)=>PixelKind::ASTC4x4SRGB,
+        (TexturePixelKind::ASTC5x5,true)=>PixelKind::ASTC5x5SRGB,
+        (TexturePixelKind::ASTC6x6,true)=>PixelKind::ASTC6x6SRGB,
+        (TexturePixelKind::ASTC8x8,true)=>PixelKind::ASTC8x8SRGB,
+        (pixel_kind,_)=>pixel_kind.into(),
+    };
+    if !state.texture_compression().supports(pixel_kind){
+        warn!("the device cannot sample {:?}, decompressing {:?} on the CPU",pixel_kind,data.path);
+        let decompressed = decompress_to_rgba8(data)?;
+        return upload_texture_data(state,&decompressed);
+    }
+    // a render target without pixels only gets storage, cameras draw its content
+    let bytes = if data.is_render_target && data.bytes.is_empty(){
+        None
+    }else{
+        Some(data.bytes.as_slice())
+    };
+    let mut gpu_texture = GPUTexture::new(
+        state,
+        data.kind.into(),
+        pixel_kind,
+        data.minification_filter.into(),
+        data.magnification_filter.into(),
+        data.mip_count as usize,
+        bytes,
+        )?;
+    TextureBinding{
+        state,
+        texture: &mut gpu_texture,
     }
+    .set_wrap(Coordinate::S,data.s_wrap_mode.into())
+    .set_wrap(Coordinate::T,data.t_wrap_mode.into())
+    .set_anisotropy(data.anisotropy);
+    return Ok(gpu_texture);
 }
 
 impl Default for Texture{
     fn default()->Self{
         return Self{
-           gpu_texture:None,
+            gpu_texture:None,
             data:None,
+            needs_upload:false,
+            release_cpu_data:false,
         };
     }
 }
 
+impl AssetMemoryUsage for Texture{
+    fn memory_usage(&self)->AssetMemory{
+        AssetMemory{
+            cpu_bytes: self.data.as_ref().map_or(0,|data|data.bytes.len()),
+            gpu_bytes: self.gpu_texture.as_ref().map_or(0,|gpu_texture|gpu_texture.size_bytes()),
+        }
+    }
+}
+
 unsafe impl Send for Texture{}
 unsafe impl Sync for Texture{}
 
@@ -75,6 +176,8 @@ pub struct TextureData {
     //serialize_content: bool,
     //data_hash: u64,
     pub(crate)is_render_target: bool,
+    /// Color data is sRGB encoded.
+    pub(crate)is_srgb: bool,
 }
 
 impl TextureData{
@@ -93,6 +196,7 @@ impl TextureData{
             //serialize_content: bool,
             //data_hash: u64,
             is_render_target:false,
+            is_srgb:false,
         }
     }
 }
@@ -120,6 +224,15 @@ pub enum TextureKind {
         /// Height of the cube face.
         height: u32,
     },
+    /// Array of 2D textures of the same size.
+    RectangleArray {
+        /// Width of a layer.
+        width: u32,
+        /// Height of a layer.
+        height: u32,
+        /// Number of layers.
+        layers: u32,
+    },
     /// Volume texture (3D).
     Volume {
         /// Width of the volume.
@@ -225,11 +338,37 @@ pub enum TexturePixelKind {
 
     /// Floating-point RGBA texture with 32bit depth.
     RGBA32F = 17,
+
+    /// Compressed ETC2 RGB (no alpha).
+    ETC2RGB8 = 18,
+
+    /// Compressed ETC2 RGB with 1 bit alpha.
+    ETC2RGB8A1 = 19,
+
+    /// Compressed ETC2 RGB with EAC alpha.
+    ETC2RGBA8 = 20,
+
+    /// Compressed EAC red, 11 bit precision.
+    EACR11 = 21,
+
+    /// Compressed EAC red and green, 11 bit precision.
+    EACRG11 = 22,
+
+    /// Compressed ASTC RGBA, 4x4 blocks.
+    ASTC4x4 = 23,
+
+    /// Compressed ASTC RGBA, 5x5 blocks.
+    ASTC5x5 = 24,
+
+    /// Compressed ASTC RGBA, 6x6 blocks.
+    ASTC6x6 = 25,
+
+    /// Compressed ASTC RGBA, 8x8 blocks.
+    ASTC8x8 = 26,
 }
 
-#[allow(dead_code)]
 impl TexturePixelKind {
-    fn new(id: u32) -> Result<Self, String> {
+    pub(crate) fn new(id: u32) -> Result<Self, String> {
         match id {
             0 => Ok(Self::R8),
             1 => Ok(Self::RGB8),
@@ -249,11 +388,20 @@ impl TexturePixelKind {
             15 => Ok(Self::RG8RGTC),
             16 => Ok(Self::RGB32F),
             17 => Ok(Self::RGBA32F),
+            18 => Ok(Self::ETC2RGB8),
+            19 => Ok(Self::ETC2RGB8A1),
+            20 => Ok(Self::ETC2RGBA8),
+            21 => Ok(Self::EACR11),
+            22 => Ok(Self::EACRG11),
+            23 => Ok(Self::ASTC4x4),
+            24 => Ok(Self::ASTC5x5),
+            25 => Ok(Self::ASTC6x6),
+            26 => Ok(Self::ASTC8x8),
             _ => Err(format!("Invalid texture kind {}!", id)),
         }
     }
 
-    fn id(self) -> u32 {
+    pub(crate) fn id(self) -> u32 {
         self as u32
     }
 }
@@ -279,6 +427,15 @@ impl From<TexturePixelKind> for PixelKind {
             TexturePixelKind::RG8RGTC => Self::RG8RGTC,
             TexturePixelKind::RGB32F => Self::RGB32F,
             TexturePixelKind::RGBA32F => Self::RGBA32F,
+            TexturePixelKind::ETC2RGB8 => Self::ETC2RGB8,
+            TexturePixelKind::ETC2RGB8A1 => Self::ETC2RGB8A1,
+            TexturePixelKind::ETC2RGBA8 => Self::ETC2RGBA8,
+            TexturePixelKind::EACR11 => Self::EACR11,
+            TexturePixelKind::EACRG11 => Self::EACRG11,
+            TexturePixelKind::ASTC4x4 => Self::ASTC4x4,
+            TexturePixelKind::ASTC5x5 => Self::ASTC5x5,
+            TexturePixelKind::ASTC6x6 => Self::ASTC6x6,
+            TexturePixelKind::ASTC8x8 => Self::ASTC8x8,
         }
     }
 }
@@ -292,6 +449,8 @@ impl From<TexturePixelKind> for PixelKind {
     Hash,
     PartialOrd,
     PartialEq,
+    Deserialize,
+    Serialize,
     )]
 #[repr(u32)]
 pub enum TextureMinificationFilter {
@@ -326,6 +485,12 @@ pub enum TextureMinificationFilter {
     LinearMipMapLinear = 5,
 }
 
+impl Default for TextureMinificationFilter {
+    fn default() -> Self {
+        Self::Linear
+    }
+}
+
 impl TextureMinificationFilter {
     /// Returns true if minification filter is using mip mapping, false - otherwise.
     pub fn is_using_mip_mapping(self) -> bool {
@@ -347,6 +512,8 @@ impl TextureMinificationFilter {
     Hash,
     PartialOrd,
     PartialEq,
+    Deserialize,
+    Serialize,
     )]
 #[repr(u32)]
 pub enum TextureMagnificationFilter {
@@ -374,6 +541,8 @@ impl Default for TextureMagnificationFilter {
     Hash,
     PartialOrd,
     PartialEq,
+    Deserialize,
+    Serialize,
     )]
 #[repr(u32)]
 pub enum TextureWrapMode {
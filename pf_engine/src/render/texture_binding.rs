@@ -1,4 +1,4 @@
-use super::{PipelineState,GPUTexture,GpuTextureKind,PixelKind,FrameworkError};
+use super::{PipelineState,GPUTexture,GpuTextureKind,PixelKind,FrameworkError,WrapMode,Coordinate};
 use glow::{HasContext, COMPRESSED_RED_RGTC1, COMPRESSED_RG_RGTC2};
 
 pub struct TextureBinding<'a> {
@@ -7,6 +7,30 @@ pub struct TextureBinding<'a> {
 }
 
 impl<'a> TextureBinding<'a>{
+    pub fn set_wrap(self,coordinate: Coordinate,wrap: WrapMode)->Self{
+        let target = self.texture.kind.gl_texture_target();
+        self.state.set_texture(0,target,Some(self.texture.texture));
+        unsafe{ self.state.gl.tex_parameter_i32(target,coordinate as u32,wrap as i32); }
+        match coordinate{
+            Coordinate::S=>self.texture.s_wrap_mode = wrap,
+            Coordinate::T=>self.texture.t_wrap_mode = wrap,
+            Coordinate::R=>self.texture.r_wrap_mode = wrap,
+        }
+        return self;
+    }
+
+    /// Values below 1 turn anisotropic filtering off. Devices without anisotropic filtering
+    /// ignore it, the rest clamp it to their maximum.
+    pub fn set_anisotropy(self,anisotropy: f32)->Self{
+        self.texture.anisotropy = anisotropy.max(1.0);
+        if self.state.supports_anisotropic_filtering(){
+            let target = self.texture.kind.gl_texture_target();
+            self.state.set_texture(0,target,Some(self.texture.texture));
+            unsafe{ self.state.gl.tex_parameter_f32(target,GL_TEXTURE_MAX_ANISOTROPY_EXT,self.texture.anisotropy); }
+        }
+        return self;
+    }
+
     pub fn set_data(
         self,
         kind: GpuTextureKind,
@@ -16,54 +40,7 @@ impl<'a> TextureBinding<'a>{
     ) -> Result<Self, FrameworkError> {
         let mip_count = mip_count.max(1);
 
-        let mut desired_byte_count = 0;
-
-        'mip_loop: for mip in 0..mip_count {
-            match kind {
-                GpuTextureKind::Line { length } => {
-                    if let Some(length) = length.checked_shr(mip as u32) {
-                        desired_byte_count += image_1d_size_bytes(pixel_kind, length);
-                    } else {
-                        break 'mip_loop;
-                    }
-                }
-                GpuTextureKind::Rectangle { width, height } => {
-                    if let (Some(width), Some(height)) = (
-                        width.checked_shr(mip as u32),
-                        height.checked_shr(mip as u32),
-                    ) {
-                        desired_byte_count += image_2d_size_bytes(pixel_kind, width, height);
-                    } else {
-                        break 'mip_loop;
-                    }
-                }
-                GpuTextureKind::Cube { width, height } => {
-                    if let (Some(width), Some(height)) = (
-                        width.checked_shr(mip as u32),
-                        height.checked_shr(mip as u32),
-                    ) {
-                        desired_byte_count += 6 * image_2d_size_bytes(pixel_kind, width, height);
-                    } else {
-                        break 'mip_loop;
-                    }
-                }
-                GpuTextureKind::Volume {
-                    width,
-                    height,
-                    depth,
-                } => {
-                    if let (Some(width), Some(height), Some(depth)) = (
-                        width.checked_shr(mip as u32),
-                        height.checked_shr(mip as u32),
-                        depth.checked_shr(mip as u32),
-                    ) {
-                        desired_byte_count += image_3d_size_bytes(pixel_kind, width, height, depth);
-                    } else {
-                        break 'mip_loop;
-                    }
-                }
-            };
-        }
+        let desired_byte_count = texture_size_bytes(kind, pixel_kind, mip_count);
 
         if let Some(data) = data {
             let actual_data_size = data.len();
@@ -77,6 +54,7 @@ impl<'a> TextureBinding<'a>{
 
         self.texture.kind = kind;
         self.texture.pixel_kind = pixel_kind;
+        self.texture.mip_count = mip_count;
 
         let target = kind.gl_texture_target();
 
@@ -106,6 +84,7 @@ impl<'a> TextureBinding<'a>{
                 PixelKind::R8 => (glow::UNSIGNED_BYTE, glow::RED, glow::R8),
                 PixelKind::R8UI => (glow::UNSIGNED_BYTE, glow::RED_INTEGER, glow::R8UI),
                 PixelKind::BGRA8 => (glow::UNSIGNED_BYTE, glow::BGRA, glow::RGBA8),
+                PixelKind::SBGRA8 => (glow::UNSIGNED_BYTE, glow::BGRA, glow::SRGB8_ALPHA8),
                 PixelKind::BGR8 => (glow::UNSIGNED_BYTE, glow::BGR, glow::RGB8),
                 PixelKind::RG16 => (glow::UNSIGNED_SHORT, glow::RG, glow::RG16),
                 PixelKind::R16 => (glow::UNSIGNED_SHORT, glow::RED, glow::R16),
@@ -120,8 +99,28 @@ impl<'a> TextureBinding<'a>{
                 PixelKind::DXT1RGBA => (0, 0, GL_COMPRESSED_RGBA_S3TC_DXT1_EXT),
                 PixelKind::DXT3RGBA => (0, 0, GL_COMPRESSED_RGBA_S3TC_DXT3_EXT),
                 PixelKind::DXT5RGBA => (0, 0, GL_COMPRESSED_RGBA_S3TC_DXT5_EXT),
+                PixelKind::DXT1SRGB => (0, 0, GL_COMPRESSED_SRGB_S3TC_DXT1_EXT),
+                PixelKind::DXT1SRGBA => (0, 0, GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT1_EXT),
+                PixelKind::DXT3SRGBA => (0, 0, GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT3_EXT),
+                PixelKind::DXT5SRGBA => (0, 0, GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT5_EXT),
                 PixelKind::R8RGTC => (0, 0, COMPRESSED_RED_RGTC1),
                 PixelKind::RG8RGTC => (0, 0, COMPRESSED_RG_RGTC2),
+                PixelKind::ETC2RGB8 => (0, 0, GL_COMPRESSED_RGB8_ETC2),
+                PixelKind::ETC2SRGB8 => (0, 0, GL_COMPRESSED_SRGB8_ETC2),
+                PixelKind::ETC2RGB8A1 => (0, 0, GL_COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2),
+                PixelKind::ETC2SRGB8A1 => (0, 0, GL_COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2),
+                PixelKind::ETC2RGBA8 => (0, 0, GL_COMPRESSED_RGBA8_ETC2_EAC),
+                PixelKind::ETC2SRGBA8 => (0, 0, GL_COMPRESSED_SRGB8_ALPHA8_ETC2_EAC),
+                PixelKind::EACR11 => (0, 0, GL_COMPRESSED_R11_EAC),
+                PixelKind::EACRG11 => (0, 0, GL_COMPRESSED_RG11_EAC),
+                PixelKind::ASTC4x4 => (0, 0, GL_COMPRESSED_RGBA_ASTC_4X4_KHR),
+                PixelKind::ASTC4x4SRGB => (0, 0, GL_COMPRESSED_SRGB8_ALPHA8_ASTC_4X4_KHR),
+                PixelKind::ASTC5x5 => (0, 0, GL_COMPRESSED_RGBA_ASTC_5X5_KHR),
+                PixelKind::ASTC5x5SRGB => (0, 0, GL_COMPRESSED_SRGB8_ALPHA8_ASTC_5X5_KHR),
+                PixelKind::ASTC6x6 => (0, 0, GL_COMPRESSED_RGBA_ASTC_6X6_KHR),
+                PixelKind::ASTC6x6SRGB => (0, 0, GL_COMPRESSED_SRGB8_ALPHA8_ASTC_6X6_KHR),
+                PixelKind::ASTC8x8 => (0, 0, GL_COMPRESSED_RGBA_ASTC_8X8_KHR),
+                PixelKind::ASTC8x8SRGB => (0, 0, GL_COMPRESSED_SRGB8_ALPHA8_ASTC_8X8_KHR),
                 PixelKind::RGB32F => (glow::FLOAT, glow::RGB, glow::RGB32F),
                 PixelKind::RGBA32F => (glow::FLOAT, glow::RGBA, glow::RGBA32F),
                 PixelKind::RGBA16F => (glow::FLOAT, glow::RGBA, glow::RGBA16F),
@@ -137,68 +136,99 @@ impl<'a> TextureBinding<'a>{
             }
 
             let mut mip_byte_offset = 0;
-            'mip_loop2: for mip in 0..mip_count {
+            for mip in 0..mip_count {
                 match kind {
                     GpuTextureKind::Line { length } => {
-                        if let Some(length) = length.checked_shr(mip as u32) {
-                            let size = image_1d_size_bytes(pixel_kind, length) as i32;
-                            let pixels = data.map(|data| {
-                                &data[mip_byte_offset..(mip_byte_offset + size as usize)]
-                            });
-
-                            if is_compressed {
-                                self.state.gl.compressed_tex_image_1d(
-                                    glow::TEXTURE_1D,
-                                    mip as i32,
-                                    internal_format as i32,
-                                    length as i32,
-                                    0,
-                                    size,
-                                    pixels.ok_or(FrameworkError::EmptyTextureData)?,
-                                );
-                            } else {
-                                self.state.gl.tex_image_1d(
-                                    glow::TEXTURE_1D,
-                                    mip as i32,
-                                    internal_format as i32,
-                                    length as i32,
-                                    0,
-                                    format,
-                                    type_,
-                                    pixels,
-                                );
-                            }
+                        let length = mip_size(length, mip);
+                        let size = image_1d_size_bytes(pixel_kind, length) as i32;
+                        let pixels = data.map(|data| {
+                            &data[mip_byte_offset..(mip_byte_offset + size as usize)]
+                        });
 
-                            mip_byte_offset += size as usize;
+                        if is_compressed {
+                            self.state.gl.compressed_tex_image_1d(
+                                glow::TEXTURE_1D,
+                                mip as i32,
+                                internal_format as i32,
+                                length as i32,
+                                0,
+                                size,
+                                pixels.ok_or(FrameworkError::EmptyTextureData)?,
+                            );
                         } else {
-                            // No need to add degenerated mips (0x1, 0x2, 4x0, etc).
-                            break 'mip_loop2;
+                            self.state.gl.tex_image_1d(
+                                glow::TEXTURE_1D,
+                                mip as i32,
+                                internal_format as i32,
+                                length as i32,
+                                0,
+                                format,
+                                type_,
+                                pixels,
+                            );
                         }
+
+                        mip_byte_offset += size as usize;
                     }
                     GpuTextureKind::Rectangle { width, height } => {
-                        if let (Some(width), Some(height)) = (
-                            width.checked_shr(mip as u32),
-                            height.checked_shr(mip as u32),
-                        ) {
-                            let size = image_2d_size_bytes(pixel_kind, width, height) as i32;
-                            let pixels = data.map(|data| {
-                                &data[mip_byte_offset..(mip_byte_offset + size as usize)]
-                            });
+                        let width = mip_size(width, mip);
+                        let height = mip_size(height, mip);
+                        let size = image_2d_size_bytes(pixel_kind, width, height) as i32;
+                        let pixels = data.map(|data| {
+                            &data[mip_byte_offset..(mip_byte_offset + size as usize)]
+                        });
+
+                        if is_compressed {
+                            self.state.gl.compressed_tex_image_2d(
+                                glow::TEXTURE_2D,
+                                mip as i32,
+                                internal_format as i32,
+                                width as i32,
+                                height as i32,
+                                0,
+                                size,
+                                pixels.ok_or(FrameworkError::EmptyTextureData)?,
+                            );
+                        } else {
+                            self.state.gl.tex_image_2d(
+                                glow::TEXTURE_2D,
+                                mip as i32,
+                                internal_format as i32,
+                                width as i32,
+                                height as i32,
+                                0,
+                                format,
+                                type_,
+                                pixels,
+                            );
+                        }
+
+                        mip_byte_offset += size as usize;
+                    }
+                    GpuTextureKind::Cube { width, height } => {
+                        let width = mip_size(width, mip);
+                        let height = mip_size(height, mip);
+                        let bytes_per_face = image_2d_size_bytes(pixel_kind, width, height);
+
+                        for face in 0..6 {
+                            let begin = mip_byte_offset + face * bytes_per_face;
+                            let end = mip_byte_offset + (face + 1) * bytes_per_face;
+                            let face_pixels = data.map(|data| &data[begin..end]);
 
                             if is_compressed {
                                 self.state.gl.compressed_tex_image_2d(
-                                    glow::TEXTURE_2D,
+                                    glow::TEXTURE_CUBE_MAP_POSITIVE_X + face as u32,
                                     mip as i32,
                                     internal_format as i32,
                                     width as i32,
                                     height as i32,
                                     0,
-                                    size,
-                                    pixels.ok_or(FrameworkError::EmptyTextureData)?,
+                                    bytes_per_face as i32,
+                                    face_pixels.ok_or(FrameworkError::EmptyTextureData)?,
                                 );
                             } else {
                                 self.state.gl.tex_image_2d(
-                                    glow::TEXTURE_2D,
+                                    glow::TEXTURE_CUBE_MAP_POSITIVE_X + face as u32,
                                     mip as i32,
                                     internal_format as i32,
                                     width as i32,
@@ -206,107 +236,95 @@ impl<'a> TextureBinding<'a>{
                                     0,
                                     format,
                                     type_,
-                                    pixels,
+                                    face_pixels,
                                 );
                             }
-
-                            mip_byte_offset += size as usize;
-                        } else {
-                            // No need to add degenerated mips (0x1, 0x2, 4x0, etc).
-                            break 'mip_loop2;
                         }
+
+                        mip_byte_offset += 6 * bytes_per_face as usize;
                     }
-                    GpuTextureKind::Cube { width, height } => {
-                        if let (Some(width), Some(height)) = (
-                            width.checked_shr(mip as u32),
-                            height.checked_shr(mip as u32),
-                        ) {
-                            let bytes_per_face = image_2d_size_bytes(pixel_kind, width, height);
-
-                            for face in 0..6 {
-                                let begin = mip_byte_offset + face * bytes_per_face;
-                                let end = mip_byte_offset + (face + 1) * bytes_per_face;
-                                let face_pixels = data.map(|data| &data[begin..end]);
-
-                                if is_compressed {
-                                    self.state.gl.compressed_tex_image_2d(
-                                        glow::TEXTURE_CUBE_MAP_POSITIVE_X + face as u32,
-                                        mip as i32,
-                                        internal_format as i32,
-                                        width as i32,
-                                        height as i32,
-                                        0,
-                                        bytes_per_face as i32,
-                                        face_pixels.ok_or(FrameworkError::EmptyTextureData)?,
-                                    );
-                                } else {
-                                    self.state.gl.tex_image_2d(
-                                        glow::TEXTURE_CUBE_MAP_POSITIVE_X + face as u32,
-                                        mip as i32,
-                                        internal_format as i32,
-                                        width as i32,
-                                        height as i32,
-                                        0,
-                                        format,
-                                        type_,
-                                        face_pixels,
-                                    );
-                                }
-                            }
+                    GpuTextureKind::RectangleArray {
+                        width,
+                        height,
+                        layers,
+                    } => {
+                        let width = mip_size(width, mip);
+                        let height = mip_size(height, mip);
+                        let size = layers * image_2d_size_bytes(pixel_kind, width, height);
+                        let pixels = data.map(|data| {
+                            &data[mip_byte_offset..(mip_byte_offset + size)]
+                        });
 
-                            mip_byte_offset += 6 * bytes_per_face as usize;
+                        if is_compressed {
+                            self.state.gl.compressed_tex_image_3d(
+                                glow::TEXTURE_2D_ARRAY,
+                                mip as i32,
+                                internal_format as i32,
+                                width as i32,
+                                height as i32,
+                                layers as i32,
+                                0,
+                                size as i32,
+                                pixels.ok_or(FrameworkError::EmptyTextureData)?,
+                            );
                         } else {
-                            // No need to add degenerated mips (0x1, 0x2, 4x0, etc).
-                            break 'mip_loop2;
+                            self.state.gl.tex_image_3d(
+                                glow::TEXTURE_2D_ARRAY,
+                                mip as i32,
+                                internal_format as i32,
+                                width as i32,
+                                height as i32,
+                                layers as i32,
+                                0,
+                                format,
+                                type_,
+                                pixels,
+                            );
                         }
+
+                        mip_byte_offset += size;
                     }
                     GpuTextureKind::Volume {
                         width,
                         height,
                         depth,
                     } => {
-                        if let (Some(width), Some(height), Some(depth)) = (
-                            width.checked_shr(mip as u32),
-                            height.checked_shr(mip as u32),
-                            depth.checked_shr(mip as u32),
-                        ) {
-                            let size = image_3d_size_bytes(pixel_kind, width, height, depth) as i32;
-                            let pixels = data.map(|data| {
-                                &data[mip_byte_offset..(mip_byte_offset + size as usize)]
-                            });
+                        let width = mip_size(width, mip);
+                        let height = mip_size(height, mip);
+                        let depth = mip_size(depth, mip);
+                        let size = image_3d_size_bytes(pixel_kind, width, height, depth) as i32;
+                        let pixels = data.map(|data| {
+                            &data[mip_byte_offset..(mip_byte_offset + size as usize)]
+                        });
 
-                            if is_compressed {
-                                self.state.gl.compressed_tex_image_3d(
-                                    glow::TEXTURE_3D,
-                                    0,
-                                    internal_format as i32,
-                                    width as i32,
-                                    height as i32,
-                                    depth as i32,
-                                    0,
-                                    size,
-                                    pixels.ok_or(FrameworkError::EmptyTextureData)?,
-                                );
-                            } else {
-                                self.state.gl.tex_image_3d(
-                                    glow::TEXTURE_3D,
-                                    0,
-                                    internal_format as i32,
-                                    width as i32,
-                                    height as i32,
-                                    depth as i32,
-                                    0,
-                                    format,
-                                    type_,
-                                    pixels,
-                                );
-                            }
-
-                            mip_byte_offset += size as usize;
+                        if is_compressed {
+                            self.state.gl.compressed_tex_image_3d(
+                                glow::TEXTURE_3D,
+                                mip as i32,
+                                internal_format as i32,
+                                width as i32,
+                                height as i32,
+                                depth as i32,
+                                0,
+                                size,
+                                pixels.ok_or(FrameworkError::EmptyTextureData)?,
+                            );
                         } else {
-                            // No need to add degenerated mips (0x1, 0x2, 4x0, etc).
-                            break 'mip_loop2;
+                            self.state.gl.tex_image_3d(
+                                glow::TEXTURE_3D,
+                                mip as i32,
+                                internal_format as i32,
+                                width as i32,
+                                height as i32,
+                                depth as i32,
+                                0,
+                                format,
+                                type_,
+                                pixels,
+                            );
                         }
+
+                        mip_byte_offset += size as usize;
                     }
                 }
             }
@@ -316,6 +334,22 @@ impl<'a> TextureBinding<'a>{
     }
 }
 
+/// Bytes of `mip_count` mip levels of a texture, largest first. Every mip of a cube holds six
+/// faces, every mip of an array all of its layers.
+pub(crate) fn texture_size_bytes(kind: GpuTextureKind,pixel_kind: PixelKind,mip_count: usize)->usize{
+    let mut desired_byte_count = 0;
+    for mip in 0..mip_count{
+        desired_byte_count += match kind{
+            GpuTextureKind::Line{length}=>image_1d_size_bytes(pixel_kind,mip_size(length,mip)),
+            GpuTextureKind::Rectangle{width,height}=>image_2d_size_bytes(pixel_kind,mip_size(width,mip),mip_size(height,mip)),
+            GpuTextureKind::Cube{width,height}=>6 * image_2d_size_bytes(pixel_kind,mip_size(width,mip),mip_size(height,mip)),
+            GpuTextureKind::RectangleArray{width,height,layers}=>layers * image_2d_size_bytes(pixel_kind,mip_size(width,mip),mip_size(height,mip)),
+            GpuTextureKind::Volume{width,height,depth}=>image_3d_size_bytes(pixel_kind,mip_size(width,mip),mip_size(height,mip),mip_size(depth,mip)),
+        };
+    }
+    return desired_byte_count;
+}
+
 fn image_1d_size_bytes(pixel_kind: PixelKind, length: usize) -> usize {
     match pixel_kind {
         PixelKind::RGBA32F => 16 * length,
@@ -325,6 +359,7 @@ fn image_1d_size_bytes(pixel_kind: PixelKind, length: usize) -> usize {
         PixelKind::RGBA8
         | PixelKind::SRGBA8
         | PixelKind::BGRA8
+        | PixelKind::SBGRA8
         | PixelKind::RG16
         | PixelKind::D24S8
         | PixelKind::D32F
@@ -334,18 +369,50 @@ fn image_1d_size_bytes(pixel_kind: PixelKind, length: usize) -> usize {
         PixelKind::RGB8 | PixelKind::SRGB8 | PixelKind::BGR8 => 3 * length,
         PixelKind::RG8 | PixelKind::R16 | PixelKind::D16 | PixelKind::F16 => 2 * length,
         PixelKind::R8 | PixelKind::R8UI => length,
-        PixelKind::DXT1RGB | PixelKind::DXT1RGBA | PixelKind::R8RGTC => {
+        PixelKind::DXT1RGB
+        | PixelKind::DXT1RGBA
+        | PixelKind::DXT1SRGB
+        | PixelKind::DXT1SRGBA
+        | PixelKind::R8RGTC => {
             let block_size = 8;
             ceil_div_4(length) * block_size
         }
-        PixelKind::DXT3RGBA | PixelKind::DXT5RGBA | PixelKind::RG8RGTC => {
+        PixelKind::DXT3RGBA
+        | PixelKind::DXT5RGBA
+        | PixelKind::DXT3SRGBA
+        | PixelKind::DXT5SRGBA
+        | PixelKind::RG8RGTC => {
             let block_size = 16;
             ceil_div_4(length) * block_size
         }
+        PixelKind::ETC2RGB8
+        | PixelKind::ETC2SRGB8
+        | PixelKind::ETC2RGB8A1
+        | PixelKind::ETC2SRGB8A1
+        | PixelKind::EACR11 => {
+            let block_size = 8;
+            ceil_div_4(length) * block_size
+        }
+        PixelKind::ETC2RGBA8 | PixelKind::ETC2SRGBA8 | PixelKind::EACRG11 => {
+            let block_size = 16;
+            ceil_div_4(length) * block_size
+        }
+        PixelKind::ASTC4x4
+        | PixelKind::ASTC4x4SRGB
+        | PixelKind::ASTC5x5
+        | PixelKind::ASTC5x5SRGB
+        | PixelKind::ASTC6x6
+        | PixelKind::ASTC6x6SRGB
+        | PixelKind::ASTC8x8
+        | PixelKind::ASTC8x8SRGB => {
+            // Every ASTC block is 128 bits whatever its footprint.
+            let (block_width, _) = astc_footprint(pixel_kind);
+            ceil_div(length, block_width) * 16
+        }
     }
 }
 
-fn image_2d_size_bytes(pixel_kind: PixelKind, width: usize, height: usize) -> usize {
+pub(crate) fn image_2d_size_bytes(pixel_kind: PixelKind, width: usize, height: usize) -> usize {
     let pixel_count = width * height;
     match pixel_kind {
         PixelKind::RGBA32F => 16 * pixel_count,
@@ -355,6 +422,7 @@ fn image_2d_size_bytes(pixel_kind: PixelKind, width: usize, height: usize) -> us
         PixelKind::RGBA8
         | PixelKind::SRGBA8
         | PixelKind::BGRA8
+        | PixelKind::SBGRA8
         | PixelKind::RG16
         | PixelKind::D24S8
         | PixelKind::D32F
@@ -364,14 +432,45 @@ fn image_2d_size_bytes(pixel_kind: PixelKind, width: usize, height: usize) -> us
         PixelKind::RGB8 | PixelKind::SRGB8 | PixelKind::BGR8 => 3 * pixel_count,
         PixelKind::RG8 | PixelKind::R16 | PixelKind::D16 | PixelKind::F16 => 2 * pixel_count,
         PixelKind::R8 | PixelKind::R8UI => pixel_count,
-        PixelKind::DXT1RGB | PixelKind::DXT1RGBA | PixelKind::R8RGTC => {
+        PixelKind::DXT1RGB
+        | PixelKind::DXT1RGBA
+        | PixelKind::DXT1SRGB
+        | PixelKind::DXT1SRGBA
+        | PixelKind::R8RGTC => {
+            let block_size = 8;
+            ceil_div_4(width) * ceil_div_4(height) * block_size
+        }
+        PixelKind::DXT3RGBA
+        | PixelKind::DXT5RGBA
+        | PixelKind::DXT3SRGBA
+        | PixelKind::DXT5SRGBA
+        | PixelKind::RG8RGTC => {
+            let block_size = 16;
+            ceil_div_4(width) * ceil_div_4(height) * block_size
+        }
+        PixelKind::ETC2RGB8
+        | PixelKind::ETC2SRGB8
+        | PixelKind::ETC2RGB8A1
+        | PixelKind::ETC2SRGB8A1
+        | PixelKind::EACR11 => {
             let block_size = 8;
             ceil_div_4(width) * ceil_div_4(height) * block_size
         }
-        PixelKind::DXT3RGBA | PixelKind::DXT5RGBA | PixelKind::RG8RGTC => {
+        PixelKind::ETC2RGBA8 | PixelKind::ETC2SRGBA8 | PixelKind::EACRG11 => {
             let block_size = 16;
             ceil_div_4(width) * ceil_div_4(height) * block_size
         }
+        PixelKind::ASTC4x4
+        | PixelKind::ASTC4x4SRGB
+        | PixelKind::ASTC5x5
+        | PixelKind::ASTC5x5SRGB
+        | PixelKind::ASTC6x6
+        | PixelKind::ASTC6x6SRGB
+        | PixelKind::ASTC8x8
+        | PixelKind::ASTC8x8SRGB => {
+            let (block_width, block_height) = astc_footprint(pixel_kind);
+            ceil_div(width, block_width) * ceil_div(height, block_height) * 16
+        }
     }
 }
 
@@ -385,6 +484,7 @@ fn image_3d_size_bytes(pixel_kind: PixelKind, width: usize, height: usize, depth
         PixelKind::RGBA8
         | PixelKind::SRGBA8
         | PixelKind::BGRA8
+        | PixelKind::SBGRA8
         | PixelKind::RG16
         | PixelKind::D24S8
         | PixelKind::D32F
@@ -394,22 +494,88 @@ fn image_3d_size_bytes(pixel_kind: PixelKind, width: usize, height: usize, depth
         PixelKind::RGB8 | PixelKind::SRGB8 | PixelKind::BGR8 => 3 * pixel_count,
         PixelKind::RG8 | PixelKind::R16 | PixelKind::D16 | PixelKind::F16 => 2 * pixel_count,
         PixelKind::R8 | PixelKind::R8UI => pixel_count,
-        PixelKind::DXT1RGB | PixelKind::DXT1RGBA | PixelKind::R8RGTC => {
+        PixelKind::DXT1RGB
+        | PixelKind::DXT1RGBA
+        | PixelKind::DXT1SRGB
+        | PixelKind::DXT1SRGBA
+        | PixelKind::R8RGTC => {
             let block_size = 8;
             ceil_div_4(width) * ceil_div_4(height) * ceil_div_4(depth) * block_size
         }
-        PixelKind::DXT3RGBA | PixelKind::DXT5RGBA | PixelKind::RG8RGTC => {
+        PixelKind::DXT3RGBA
+        | PixelKind::DXT5RGBA
+        | PixelKind::DXT3SRGBA
+        | PixelKind::DXT5SRGBA
+        | PixelKind::RG8RGTC => {
             let block_size = 16;
             ceil_div_4(width) * ceil_div_4(height) * ceil_div_4(depth) * block_size
         }
+        // ETC2 and 2D ASTC blocks cover one slice, a volume holds `depth` of them.
+        PixelKind::ETC2RGB8
+        | PixelKind::ETC2SRGB8
+        | PixelKind::ETC2RGB8A1
+        | PixelKind::ETC2SRGB8A1
+        | PixelKind::EACR11
+        | PixelKind::ETC2RGBA8
+        | PixelKind::ETC2SRGBA8
+        | PixelKind::EACRG11
+        | PixelKind::ASTC4x4
+        | PixelKind::ASTC4x4SRGB
+        | PixelKind::ASTC5x5
+        | PixelKind::ASTC5x5SRGB
+        | PixelKind::ASTC6x6
+        | PixelKind::ASTC6x6SRGB
+        | PixelKind::ASTC8x8
+        | PixelKind::ASTC8x8SRGB => depth * image_2d_size_bytes(pixel_kind, width, height),
     }
 }
 
+const GL_TEXTURE_MAX_ANISOTROPY_EXT: u32 = 0x84FE;
 const GL_COMPRESSED_RGB_S3TC_DXT1_EXT: u32 = 0x83F0;
 const GL_COMPRESSED_RGBA_S3TC_DXT1_EXT: u32 = 0x83F1;
 const GL_COMPRESSED_RGBA_S3TC_DXT3_EXT: u32 = 0x83F2;
 const GL_COMPRESSED_RGBA_S3TC_DXT5_EXT: u32 = 0x83F3;
+const GL_COMPRESSED_SRGB_S3TC_DXT1_EXT: u32 = 0x8C4C;
+const GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT1_EXT: u32 = 0x8C4D;
+const GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT3_EXT: u32 = 0x8C4E;
+const GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT5_EXT: u32 = 0x8C4F;
+const GL_COMPRESSED_R11_EAC: u32 = 0x9270;
+const GL_COMPRESSED_RG11_EAC: u32 = 0x9272;
+const GL_COMPRESSED_RGB8_ETC2: u32 = 0x9274;
+const GL_COMPRESSED_SRGB8_ETC2: u32 = 0x9275;
+const GL_COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2: u32 = 0x9276;
+const GL_COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2: u32 = 0x9277;
+const GL_COMPRESSED_RGBA8_ETC2_EAC: u32 = 0x9278;
+const GL_COMPRESSED_SRGB8_ALPHA8_ETC2_EAC: u32 = 0x9279;
+const GL_COMPRESSED_RGBA_ASTC_4X4_KHR: u32 = 0x93B0;
+const GL_COMPRESSED_RGBA_ASTC_5X5_KHR: u32 = 0x93B2;
+const GL_COMPRESSED_RGBA_ASTC_6X6_KHR: u32 = 0x93B4;
+const GL_COMPRESSED_RGBA_ASTC_8X8_KHR: u32 = 0x93B7;
+const GL_COMPRESSED_SRGB8_ALPHA8_ASTC_4X4_KHR: u32 = 0x93D0;
+const GL_COMPRESSED_SRGB8_ALPHA8_ASTC_5X5_KHR: u32 = 0x93D2;
+const GL_COMPRESSED_SRGB8_ALPHA8_ASTC_6X6_KHR: u32 = 0x93D4;
+const GL_COMPRESSED_SRGB8_ALPHA8_ASTC_8X8_KHR: u32 = 0x93D7;
+
+/// Size of a mip level along one axis, mips never shrink below one pixel.
+pub(crate) fn mip_size(size: usize,mip: usize)->usize{
+    size.checked_shr(mip as u32).unwrap_or(0).max(1)
+}
 
 fn ceil_div_4(x: usize) -> usize {
     (x + 3) / 4
 }
+
+fn ceil_div(x: usize,block: usize)->usize{
+    (x + block - 1) / block
+}
+
+/// Block width and height of an ASTC pixel kind.
+pub(crate) fn astc_footprint(pixel_kind: PixelKind)->(usize,usize){
+    match pixel_kind{
+        PixelKind::ASTC4x4|PixelKind::ASTC4x4SRGB=>(4,4),
+        PixelKind::ASTC5x5|PixelKind::ASTC5x5SRGB=>(5,5),
+        PixelKind::ASTC6x6|PixelKind::ASTC6x6SRGB=>(6,6),
+        PixelKind::ASTC8x8|PixelKind::ASTC8x8SRGB=>(8,8),
+        _=>(1,1),
+    }
+}
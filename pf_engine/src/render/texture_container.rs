@@ -0,0 +1,512 @@
+//! KTX2 and DDS files, read by `TextureAssetLoader`. Both may hold a mip chain, the six faces
+//! of a cube or the layers of an array. They are reordered into the layout of `TextureData`:
+//! every mip level largest first, each with all of its faces or layers.
+//!
+//! Supercompressed KTX2 files (Basis Universal, Zstandard) and cube map arrays are not
+//! supported.
+
+use super::texture::{TextureData,TextureKind,TexturePixelKind,TextureBytes};
+use super::texture_binding::{texture_size_bytes};
+use super::GpuTextureKind;
+use crate::core::byteorder::{LittleEndian,ReadBytesExt};
+use std::io::{self,Cursor,Read};
+use std::path::PathBuf;
+
+pub const KTX2_MAGIC: &[u8; 12] = &[0xAB, b'K', b'T', b'X', b' ', b'2', b'0', 0xBB, b'\r', b'\n', 0x1A, b'\n'];
+pub const DDS_MAGIC: &[u8; 4] = b"DDS ";
+
+pub fn read_ktx2(bytes: &[u8],path: PathBuf)->Result<TextureData,String>{
+    let mut cursor = Cursor::new(bytes);
+    return read_ktx2_texture(&mut cursor,path).map_err(|e|e.to_string());
+}
+
+pub fn read_dds(bytes: &[u8],path: PathBuf)->Result<TextureData,String>{
+    let mut cursor = Cursor::new(bytes);
+    return read_dds_texture(&mut cursor,path).map_err(|e|e.to_string());
+}
+
+fn read_ktx2_texture(cursor: &mut Cursor<&[u8]>,path: PathBuf)->io::Result<TextureData>{
+    let mut magic = [0u8;12];
+    cursor.read_exact(&mut magic)?;
+    if &magic != KTX2_MAGIC{
+        return Err(invalid_data("not a KTX2 file".to_string()));
+    }
+    let vk_format = cursor.read_u32::<LittleEndian>()?;
+    let _type_size = cursor.read_u32::<LittleEndian>()?;
+    let width = cursor.read_u32::<LittleEndian>()?;
+    let height = cursor.read_u32::<LittleEndian>()?;
+    let depth = cursor.read_u32::<LittleEndian>()?;
+    let layer_count = cursor.read_u32::<LittleEndian>()?;
+    let face_count = cursor.read_u32::<LittleEndian>()?;
+    // zero asks the reader to generate the mips, there is only the base level then
+    let level_count = cursor.read_u32::<LittleEndian>()?.max(1);
+    let supercompression = cursor.read_u32::<LittleEndian>()?;
+    if supercompression != 0{
+        return Err(invalid_data(format!("supercompression scheme {} is not supported",supercompression)));
+    }
+    // data format descriptor, key/value data and supercompression global data are not needed
+    let mut index = [0u8;32];
+    cursor.read_exact(&mut index)?;
+
+    let (pixel_kind,is_srgb) = ktx2_pixel_kind(vk_format)?;
+    let kind = match (face_count,layer_count,depth,height){
+        (6,0 | 1,_,_)=>TextureKind::Cube{width,height},
+        (6,_,_,_)=>return Err(invalid_data("cube map arrays are not supported".to_string())),
+        (1,0 | 1,0 | 1,0)=>TextureKind::Line{length:width},
+        (1,0 | 1,0 | 1,_)=>TextureKind::Rectangle{width,height},
+        (1,0 | 1,_,_)=>TextureKind::Volume{width,height,depth},
+        (1,layers,0 | 1,_)=>TextureKind::RectangleArray{width,height:height.max(1),layers},
+        _=>return Err(invalid_data(format!(
+            "invalid layout, {} faces, {} layers, depth {}",face_count,layer_count,depth))),
+    };
+    check_mip_count(kind,level_count)?;
+
+    let size = checked_texture_size_bytes(kind,pixel_kind,level_count)?;
+    let mip_offsets: Vec<usize> = (0..=level_count as usize)
+        .map(|mip|texture_size_bytes(kind.into(),pixel_kind.into(),mip))
+        .collect();
+    // levels may be stored smallest first, the index is largest first
+    let file = *cursor.get_ref();
+    let mut levels = Vec::with_capacity(level_count as usize);
+    for level in 0..level_count as usize{
+        let offset = cursor.read_u64::<LittleEndian>()?;
+        let length = cursor.read_u64::<LittleEndian>()?;
+        let _uncompressed_length = cursor.read_u64::<LittleEndian>()?;
+        let expected = mip_offsets[level + 1] - mip_offsets[level];
+        if length != expected as u64{
+            return Err(invalid_data(format!("mip level {} holds {} bytes instead of {}",level,length,expected)));
+        }
+        let level_bytes = usize::try_from(offset).ok()
+            .and_then(|offset|file.get(offset..offset.checked_add(expected)?))
+            .ok_or_else(||invalid_data(format!("mip level {} is out of the file",level)))?;
+        levels.push(level_bytes);
+    }
+    let mut bytes = Vec::with_capacity(size);
+    for level_bytes in levels{
+        bytes.extend_from_slice(level_bytes);
+    }
+    return new_texture_data(path,kind,pixel_kind,is_srgb,level_count,bytes);
+}
+
+/// `TexturePixelKind` of a `VkFormat`, and whether it is sRGB.
+fn ktx2_pixel_kind(vk_format: u32)->io::Result<(TexturePixelKind,bool)>{
+    let pixel_kind = match vk_format{
+        9=>(TexturePixelKind::R8,false),
+        16=>(TexturePixelKind::RG8,false),
+        23=>(TexturePixelKind::RGB8,false),
+        29=>(TexturePixelKind::RGB8,true),
+        30=>(TexturePixelKind::BGR8,false),
+        37=>(TexturePixelKind::RGBA8,false),
+        43=>(TexturePixelKind::RGBA8,true),
+        44=>(TexturePixelKind::BGRA8,false),
+        70=>(TexturePixelKind::R16,false),
+        77=>(TexturePixelKind::RG16,false),
+        84=>(TexturePixelKind::RGB16,false),
+        91=>(TexturePixelKind::RGBA16,false),
+        106=>(TexturePixelKind::RGB32F,false),
+        109=>(TexturePixelKind::RGBA32F,false),
+        131=>(TexturePixelKind::DXT1RGB,false),
+        132=>(TexturePixelKind::DXT1RGB,true),
+        133=>(TexturePixelKind::DXT1RGBA,false),
+        134=>(TexturePixelKind::DXT1RGBA,true),
+        135=>(TexturePixelKind::DXT3RGBA,false),
+        136=>(TexturePixelKind::DXT3RGBA,true),
+        137=>(TexturePixelKind::DXT5RGBA,false),
+        138=>(TexturePixelKind::DXT5RGBA,true),
+        139=>(TexturePixelKind::R8RGTC,false),
+        141=>(TexturePixelKind::RG8RGTC,false),
+        147=>(TexturePixelKind::ETC2RGB8,false),
+        148=>(TexturePixelKind::ETC2RGB8,true),
+        149=>(TexturePixelKind::ETC2RGB8A1,false),
+        150=>(TexturePixelKind::ETC2RGB8A1,true),
+        151=>(TexturePixelKind::ETC2RGBA8,false),
+        152=>(TexturePixelKind::ETC2RGBA8,true),
+        153=>(TexturePixelKind::EACR11,false),
+        155=>(TexturePixelKind::EACRG11,false),
+        157=>(TexturePixelKind::ASTC4x4,false),
+        158=>(TexturePixelKind::ASTC4x4,true),
+        161=>(TexturePixelKind::ASTC5x5,false),
+        162=>(TexturePixelKind::ASTC5x5,true),
+        165=>(TexturePixelKind::ASTC6x6,false),
+        166=>(TexturePixelKind::ASTC6x6,true),
+        171=>(TexturePixelKind::ASTC8x8,false),
+        172=>(TexturePixelKind::ASTC8x8,true),
+        0=>return Err(invalid_data("textures without a format, such as Basis Universal ones, are not supported".to_string())),
+        _=>return Err(invalid_data(format!("unsupported VkFormat {}",vk_format))),
+    };
+    return Ok(pixel_kind);
+}
+
+const DDSD_MIPMAPCOUNT: u32 = 0x2_0000;
+const DDPF_ALPHAPIXELS: u32 = 0x1;
+const DDPF_FOURCC: u32 = 0x4;
+const DDPF_RGB: u32 = 0x40;
+const DDPF_LUMINANCE: u32 = 0x2_0000;
+const DDSCAPS2_CUBEMAP: u32 = 0x200;
+const DDSCAPS2_CUBEMAP_ALLFACES: u32 = 0xFC00;
+const DDSCAPS2_VOLUME: u32 = 0x20_0000;
+const DDS_RESOURCE_MISC_TEXTURECUBE: u32 = 0x4;
+
+fn read_dds_texture(cursor: &mut Cursor<&[u8]>,path: PathBuf)->io::Result<TextureData>{
+    let mut magic = [0u8;4];
+    cursor.read_exact(&mut magic)?;
+    if &magic != DDS_MAGIC{
+        return Err(invalid_data("not a DDS file".to_string()));
+    }
+    let _size = cursor.read_u32::<LittleEndian>()?;
+    let flags = cursor.read_u32::<LittleEndian>()?;
+    let height = cursor.read_u32::<LittleEndian>()?;
+    let width = cursor.read_u32::<LittleEndian>()?;
+    let _pitch_or_linear_size = cursor.read_u32::<LittleEndian>()?;
+    let depth = cursor.read_u32::<LittleEndian>()?;
+    let mip_count = cursor.read_u32::<LittleEndian>()?;
+    let mip_count = if flags & DDSD_MIPMAPCOUNT != 0{mip_count.max(1)}else{1};
+    cursor.set_position(cursor.position() + 11 * 4);
+    let _pixel_format_size = cursor.read_u32::<LittleEndian>()?;
+    let pixel_format_flags = cursor.read_u32::<LittleEndian>()?;
+    let mut four_cc = [0u8;4];
+    cursor.read_exact(&mut four_cc)?;
+    let bit_count = cursor.read_u32::<LittleEndian>()?;
+    let masks = (
+        cursor.read_u32::<LittleEndian>()?,
+        cursor.read_u32::<LittleEndian>()?,
+        cursor.read_u32::<LittleEndian>()?,
+        cursor.read_u32::<LittleEndian>()?,
+    );
+    let _caps = cursor.read_u32::<LittleEndian>()?;
+    let caps2 = cursor.read_u32::<LittleEndian>()?;
+    cursor.set_position(cursor.position() + 3 * 4);
+
+    let ((pixel_kind,is_srgb),kind) = if pixel_format_flags & DDPF_FOURCC != 0 && &four_cc == b"DX10"{
+        let dxgi_format = cursor.read_u32::<LittleEndian>()?;
+        let dimension = cursor.read_u32::<LittleEndian>()?;
+        let misc_flags = cursor.read_u32::<LittleEndian>()?;
+        let array_size = cursor.read_u32::<LittleEndian>()?.max(1);
+        let _misc_flags2 = cursor.read_u32::<LittleEndian>()?;
+        let is_cube = misc_flags & DDS_RESOURCE_MISC_TEXTURECUBE != 0;
+        let kind = match (dimension,is_cube,array_size){
+            (2,_,1)=>TextureKind::Line{length:width},
+            (3,true,1)=>TextureKind::Cube{width,height},
+            (3,true,_)=>return Err(invalid_data("cube map arrays are not supported".to_string())),
+            (3,false,1)=>TextureKind::Rectangle{width,height},
+            (3,false,layers)=>TextureKind::RectangleArray{width,height,layers},
+            (4,_,1)=>TextureKind::Volume{width,height,depth},
+            _=>return Err(invalid_data(format!(
+                "unsupported resource dimension {} with {} elements",dimension,array_size))),
+        };
+        (dxgi_pixel_kind(dxgi_format)?,kind)
+    }else{
+        let kind = if caps2 & DDSCAPS2_CUBEMAP != 0{
+            if caps2 & DDSCAPS2_CUBEMAP_ALLFACES != DDSCAPS2_CUBEMAP_ALLFACES{
+                return Err(invalid_data("cube maps without all six faces are not supported".to_string()));
+            }
+            TextureKind::Cube{width,height}
+        }else if caps2 & DDSCAPS2_VOLUME != 0{
+            TextureKind::Volume{width,height,depth:depth.max(1)}
+        }else{
+            TextureKind::Rectangle{width,height}
+        };
+        (legacy_dds_pixel_kind(pixel_format_flags,four_cc,bit_count,masks)?,kind)
+    };
+
+    // DDS stores the whole mip chain of a face or layer before the next one
+    let (image_kind,image_count) = match kind{
+        TextureKind::Cube{width,height}=>(TextureKind::Rectangle{width,height},6),
+        TextureKind::RectangleArray{width,height,layers}=>(TextureKind::Rectangle{width,height},layers as usize),
+        kind=>(kind,1),
+    };
+    check_mip_count(kind,mip_count)?;
+    let size = checked_texture_size_bytes(kind,pixel_kind,mip_count)?;
+    let image_kind: GpuTextureKind = image_kind.into();
+    // every offset below is within `size`, which is known to fit
+    let mip_offsets: Vec<usize> = (0..=mip_count as usize)
+        .map(|mip|texture_size_bytes(image_kind,pixel_kind.into(),mip))
+        .collect();
+    let chain_size = mip_offsets[mip_count as usize];
+    let start = cursor.position() as usize;
+    let file = *cursor.get_ref();
+    let available = file.len().saturating_sub(start);
+    if available < size{
+        return Err(invalid_data(format!("texture data is truncated, {} of {} bytes",available,size)));
+    }
+    let mut bytes = Vec::with_capacity(size);
+    for mip in 0..mip_count as usize{
+        for image in 0..image_count{
+            let begin = start + image * chain_size + mip_offsets[mip];
+            let end = start + image * chain_size + mip_offsets[mip + 1];
+            bytes.extend_from_slice(&file[begin..end]);
+        }
+    }
+    return new_texture_data(path,kind,pixel_kind,is_srgb,mip_count,bytes);
+}
+
+/// `TexturePixelKind` of a `DXGI_FORMAT`, and whether it is sRGB.
+fn dxgi_pixel_kind(dxgi_format: u32)->io::Result<(TexturePixelKind,bool)>{
+    let pixel_kind = match dxgi_format{
+        2=>(TexturePixelKind::RGBA32F,false),
+        6=>(TexturePixelKind::RGB32F,false),
+        11=>(TexturePixelKind::RGBA16,false),
+        28=>(TexturePixelKind::RGBA8,false),
+        29=>(TexturePixelKind::RGBA8,true),
+        35=>(TexturePixelKind::RG16,false),
+        49=>(TexturePixelKind::RG8,false),
+        56=>(TexturePixelKind::R16,false),
+        61=>(TexturePixelKind::R8,false),
+        71=>(TexturePixelKind::DXT1RGBA,false),
+        72=>(TexturePixelKind::DXT1RGBA,true),
+        74=>(TexturePixelKind::DXT3RGBA,false),
+        75=>(TexturePixelKind::DXT3RGBA,true),
+        77=>(TexturePixelKind::DXT5RGBA,false),
+        78=>(TexturePixelKind::DXT5RGBA,true),
+        80=>(TexturePixelKind::R8RGTC,false),
+        83=>(TexturePixelKind::RG8RGTC,false),
+        87=>(TexturePixelKind::BGRA8,false),
+        91=>(TexturePixelKind::BGRA8,true),
+        _=>return Err(invalid_data(format!("unsupported DXGI format {}",dxgi_format))),
+    };
+    return Ok(pixel_kind);
+}
+
+/// `TexturePixelKind` of a DDS file without the DX10 header.
+fn legacy_dds_pixel_kind(flags: u32,four_cc: [u8;4],bit_count: u32,masks: (u32,u32,u32,u32))->io::Result<(TexturePixelKind,bool)>{
+    if flags & DDPF_FOURCC != 0{
+        let pixel_kind = match &four_cc{
+            b"DXT1" if flags & DDPF_ALPHAPIXELS != 0=>TexturePixelKind::DXT1RGBA,
+            b"DXT1"=>TexturePixelKind::DXT1RGB,
+            b"DXT2" | b"DXT3"=>TexturePixelKind::DXT3RGBA,
+            b"DXT4" | b"DXT5"=>TexturePixelKind::DXT5RGBA,
+            b"ATI1" | b"BC4U"=>TexturePixelKind::R8RGTC,
+            b"ATI2" | b"BC5U"=>TexturePixelKind::RG8RGTC,
+            // D3DFMT_A32B32G32R32F is stored as a number
+            _ if u32::from_le_bytes(four_cc) == 116=>TexturePixelKind::RGBA32F,
+            _=>return Err(invalid_data(format!("unsupported FourCC {:?}",String::from_utf8_lossy(&four_cc)))),
+        };
+        return Ok((pixel_kind,false));
+    }
+    let (red,green,blue,_alpha) = masks;
+    let pixel_kind = if flags & DDPF_RGB != 0{
+        match (bit_count,red,green,blue){
+            (32,0xFF,0xFF00,0xFF_0000)=>TexturePixelKind::RGBA8,
+            (32,0xFF_0000,0xFF00,0xFF)=>TexturePixelKind::BGRA8,
+            (24,0xFF,0xFF00,0xFF_0000)=>TexturePixelKind::RGB8,
+            (24,0xFF_0000,0xFF00,0xFF)=>TexturePixelKind::BGR8,
+            _=>return Err(invalid_data(format!("unsupported {} bit RGB layout",bit_count))),
+        }
+    }else if flags & DDPF_LUMINANCE != 0{
+        match (bit_count,flags & DDPF_ALPHAPIXELS != 0){
+            (8,false)=>TexturePixelKind::R8,
+            (16,true)=>TexturePixelKind::RG8,
+            (16,false)=>TexturePixelKind::R16,
+            _=>return Err(invalid_data(format!("unsupported {} bit luminance layout",bit_count))),
+        }
+    }else{
+        return Err(invalid_data("unsupported pixel format".to_string()));
+    };
+    return Ok((pixel_kind,false));
+}
+
+/// Width, height, depth and face or layer count of `kind`.
+fn texture_extent(kind: TextureKind)->(u32,u32,u32,u32){
+    match kind{
+        TextureKind::Line{length}=>(length,1,1,1),
+        TextureKind::Rectangle{width,height}=>(width,height,1,1),
+        TextureKind::Cube{width,height}=>(width,height,1,6),
+        TextureKind::RectangleArray{width,height,layers}=>(width,height,1,layers),
+        TextureKind::Volume{width,height,depth}=>(width,height,depth,1),
+    }
+}
+
+/// Fails when a file claims more mips than the full chain of `kind`, down to one pixel. A side
+/// of a `u32` has at most 32 of them.
+fn check_mip_count(kind: TextureKind,mip_count: u32)->io::Result<()>{
+    let (width,height,depth,_) = texture_extent(kind);
+    let full_chain = 32 - width.max(height).max(depth).max(1).leading_zeros();
+    if mip_count > full_chain{
+        return Err(invalid_data(format!("{} mip levels, a full chain has {}",mip_count,full_chain)));
+    }
+    return Ok(());
+}
+
+/// `texture_size_bytes`, failing instead of overflowing. No mip is larger than the base level
+/// and no pixel kind takes more than 16 bytes a pixel, so that bound is checked first.
+fn checked_texture_size_bytes(kind: TextureKind,pixel_kind: TexturePixelKind,mip_count: u32)->io::Result<usize>{
+    let (width,height,depth,images) = texture_extent(kind);
+    [width,height,depth,images,16,mip_count].iter()
+        .try_fold(1usize,|bound,&factor|bound.checked_mul(factor.max(1) as usize))
+        .ok_or_else(||invalid_data(format!("a {:?} texture of {:?} is too large",kind,pixel_kind)))?;
+    return Ok(texture_size_bytes(kind.into(),pixel_kind.into(),mip_count as usize));
+}
+
+fn new_texture_data(
+    path: PathBuf,
+    kind: TextureKind,
+    pixel_kind: TexturePixelKind,
+    is_srgb: bool,
+    mip_count: u32,
+    bytes: Vec<u8>,
+    )->io::Result<TextureData>{
+    let expected = texture_size_bytes(kind.into(),pixel_kind.into(),mip_count as usize);
+    if bytes.len() != expected{
+        return Err(invalid_data(format!("expected {} bytes of {:?} texture data, got {}",expected,pixel_kind,bytes.len())));
+    }
+    let mut data = TextureData::new(path,Vec::new());
+    data.kind = kind;
+    data.bytes = TextureBytes(bytes);
+    data.pixel_kind = pixel_kind;
+    data.mip_count = mip_count;
+    data.is_srgb = is_srgb;
+    return Ok(data);
+}
+
+fn invalid_data(message: String)->io::Error{
+    io::Error::new(io::ErrorKind::InvalidData,message)
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::core::byteorder::{WriteBytesExt};
+
+    const RGBA8_VK_FORMAT: u32 = 37;
+    const RGBA8_DXGI_FORMAT: u32 = 28;
+
+    /// A KTX2 file with its levels stored smallest first, as the format recommends.
+    fn ktx2(width: u32,height: u32,layers: u32,faces: u32,level_count: u32,levels: &[Vec<u8>])->Vec<u8>{
+        let mut file = KTX2_MAGIC.to_vec();
+        for value in [RGBA8_VK_FORMAT,1,width,height,0,layers,faces,level_count,0]{
+            file.write_u32::<LittleEndian>(value).unwrap();
+        }
+        file.extend_from_slice(&[0u8;32]);
+        let mut offset = file.len() + 24 * levels.len() + levels.iter().map(Vec::len).sum::<usize>();
+        for level in levels{
+            offset -= level.len();
+            file.write_u64::<LittleEndian>(offset as u64).unwrap();
+            file.write_u64::<LittleEndian>(level.len() as u64).unwrap();
+            file.write_u64::<LittleEndian>(level.len() as u64).unwrap();
+        }
+        for level in levels.iter().rev(){
+            file.extend_from_slice(level);
+        }
+        return file;
+    }
+
+    /// A DDS file of RGBA8 pixels, with the DX10 header when `array_size` is given.
+    fn dds(width: u32,height: u32,depth: u32,mip_count: u32,caps2: u32,array_size: Option<u32>,data: &[u8])->Vec<u8>{
+        let mut file = DDS_MAGIC.to_vec();
+        for value in [124,DDSD_MIPMAPCOUNT,height,width,0,depth,mip_count]{
+            file.write_u32::<LittleEndian>(value).unwrap();
+        }
+        file.extend_from_slice(&[0u8;44]);
+        let pixel_format = match array_size{
+            Some(_)=>[32,DDPF_FOURCC,u32::from_le_bytes(*b"DX10"),0,0,0,0,0],
+            None=>[32,DDPF_RGB | DDPF_ALPHAPIXELS,0,32,0xFF,0xFF00,0xFF_0000,0xFF00_0000],
+        };
+        for value in pixel_format.into_iter().chain([0x1000,caps2,0,0,0]){
+            file.write_u32::<LittleEndian>(value).unwrap();
+        }
+        if let Some(array_size) = array_size{
+            for value in [RGBA8_DXGI_FORMAT,3,0,array_size,0]{
+                file.write_u32::<LittleEndian>(value).unwrap();
+            }
+        }
+        file.extend_from_slice(data);
+        return file;
+    }
+
+    /// Bytes of `images` mip chains of a 2x2 RGBA8 image stored one after another, every byte
+    /// is `image * 10 + mip`.
+    fn image_chains(images: u8)->Vec<u8>{
+        let mut data = Vec::new();
+        for image in 0..images{
+            data.extend(std::iter::repeat(image * 10).take(16));
+            data.extend(std::iter::repeat(image * 10 + 1).take(4));
+        }
+        return data;
+    }
+
+    /// The same bytes in the layout of `TextureData`, every mip with all of its images.
+    fn mip_major(images: u8)->Vec<u8>{
+        let mut data = Vec::new();
+        for image in 0..images{
+            data.extend(std::iter::repeat(image * 10).take(16));
+        }
+        for image in 0..images{
+            data.extend(std::iter::repeat(image * 10 + 1).take(4));
+        }
+        return data;
+    }
+
+    #[test]
+    fn ktx2_levels_load_largest_first(){
+        let levels = vec![vec![1u8;32],vec![2u8;8],vec![3u8;4]];
+        let data = read_ktx2(&ktx2(4,2,0,1,3,&levels),PathBuf::from("a.ktx2")).unwrap();
+        assert!(matches!(data.kind,TextureKind::Rectangle{width:4,height:2}));
+        assert_eq!(data.pixel_kind,TexturePixelKind::RGBA8);
+        assert_eq!(data.mip_count,3);
+        assert_eq!(data.bytes.0,levels.concat());
+    }
+
+    #[test]
+    fn dds_mip_chain_loads(){
+        let data: Vec<u8> = (0..44).collect();
+        let texture = read_dds(&dds(4,2,0,3,0,None,&data),PathBuf::from("a.dds")).unwrap();
+        assert!(matches!(texture.kind,TextureKind::Rectangle{width:4,height:2}));
+        assert_eq!(texture.pixel_kind,TexturePixelKind::RGBA8);
+        assert_eq!(texture.mip_count,3);
+        assert_eq!(texture.bytes.0,data);
+    }
+
+    #[test]
+    fn dds_cube_faces_are_reordered_mip_major(){
+        let file = dds(2,2,0,2,DDSCAPS2_CUBEMAP | DDSCAPS2_CUBEMAP_ALLFACES,None,&image_chains(6));
+        let texture = read_dds(&file,PathBuf::from("a.dds")).unwrap();
+        assert!(matches!(texture.kind,TextureKind::Cube{width:2,height:2}));
+        assert_eq!(texture.bytes.0,mip_major(6));
+    }
+
+    #[test]
+    fn dds_array_layers_are_reordered_mip_major(){
+        let texture = read_dds(&dds(2,2,0,2,0,Some(3),&image_chains(3)),PathBuf::from("a.dds")).unwrap();
+        assert!(matches!(texture.kind,TextureKind::RectangleArray{width:2,height:2,layers:3}));
+        assert_eq!(texture.bytes.0,mip_major(3));
+    }
+
+    #[test]
+    fn truncated_files_fail(){
+        let ktx2 = ktx2(4,2,0,1,3,&[vec![1u8;32],vec![2u8;8],vec![3u8;4]]);
+        let dds = dds(4,2,0,3,0,None,&[0u8;44]);
+        for length in [0,20,79,ktx2.len() - 1]{
+            assert!(read_ktx2(&ktx2[..length],PathBuf::from("a.ktx2")).is_err());
+        }
+        for length in [0,20,127,dds.len() - 1]{
+            assert!(read_dds(&dds[..length],PathBuf::from("a.dds")).is_err());
+        }
+        // large enough to fit a usize, far larger than the file
+        assert!(read_dds(&dds(65536,65536,0,1,0,None,&[]),PathBuf::from("a.dds")).is_err());
+    }
+
+    #[test]
+    fn ktx2_level_of_the_wrong_size_fails(){
+        let file = ktx2(4,2,0,1,2,&[vec![1u8;32],vec![2u8;4]]);
+        assert!(read_ktx2(&file,PathBuf::from("a.ktx2")).is_err());
+    }
+
+    #[test]
+    fn mip_counts_beyond_a_full_chain_fail(){
+        let levels = vec![vec![1u8;32],vec![2u8;8],vec![3u8;4],vec![4u8;4]];
+        assert!(read_ktx2(&ktx2(4,2,0,1,4,&levels),PathBuf::from("a.ktx2")).is_err());
+        assert!(read_ktx2(&ktx2(4,2,0,1,u32::MAX,&[]),PathBuf::from("a.ktx2")).is_err());
+        assert!(read_dds(&dds(4,2,0,4,0,None,&[0u8;48]),PathBuf::from("a.dds")).is_err());
+        assert!(read_dds(&dds(4,2,0,u32::MAX,0,None,&[]),PathBuf::from("a.dds")).is_err());
+    }
+
+    #[test]
+    fn overflowing_dimensions_fail(){
+        let file = ktx2(u32::MAX,u32::MAX,u32::MAX,1,1,&[]);
+        assert!(read_ktx2(&file,PathBuf::from("a.ktx2")).is_err());
+        let file = dds(u32::MAX,u32::MAX,u32::MAX,1,DDSCAPS2_VOLUME,None,&[]);
+        assert!(read_dds(&file,PathBuf::from("a.dds")).is_err());
+        let file = dds(u32::MAX,u32::MAX,0,1,0,Some(u32::MAX),&[]);
+        assert!(read_dds(&file,PathBuf::from("a.dds")).is_err());
+    }
+}
@@ -0,0 +1,87 @@
+use super::texture::{TextureData,TextureKind,TexturePixelKind,TextureBytes};
+use super::texture_binding::{image_2d_size_bytes,mip_size,astc_footprint};
+use super::PixelKind;
+
+/// Decodes every mip, face and layer of a compressed texture into `TexturePixelKind::RGBA8`,
+/// for devices that cannot sample its pixel kind. Everything else of `data` is kept, sRGB
+/// data stays sRGB. DXT3 is not supported.
+pub fn decompress_to_rgba8(data: &TextureData)->Result<TextureData,String>{
+    let pixel_kind: PixelKind = data.pixel_kind.into();
+    if !pixel_kind.is_compressed(){
+        return Err(format!("{:?} is not compressed",pixel_kind));
+    }
+    // a 1D texture is a 2D one a pixel high, a volume one 2D image per slice
+    let (width,height,depth,layers) = match data.kind{
+        TextureKind::Line{length}=>(length,1,1,1),
+        TextureKind::Rectangle{width,height}=>(width,height,1,1),
+        TextureKind::Cube{width,height}=>(width,height,1,6),
+        TextureKind::RectangleArray{width,height,layers}=>(width,height,1,layers),
+        TextureKind::Volume{width,height,depth}=>(width,height,depth,1),
+    };
+
+    let mut rgba = Vec::new();
+    let mut offset = 0;
+    for mip in 0..data.mip_count.max(1) as usize{
+        let (width,height,depth) = (mip_size(width as usize,mip),mip_size(height as usize,mip),mip_size(depth as usize,mip));
+        let image_size = image_2d_size_bytes(pixel_kind,width,height);
+        for _ in 0..depth * layers as usize{
+            let image = data.bytes.get(offset..offset + image_size)
+                .ok_or_else(||format!("texture data is truncated at mip {}",mip))?;
+            decode_image(pixel_kind,image,width,height,&mut rgba)?;
+            offset += image_size;
+        }
+    }
+
+    let mut decompressed = TextureData::new(data.path.clone(),Vec::new());
+    decompressed.kind = data.kind;
+    decompressed.bytes = TextureBytes(rgba);
+    decompressed.pixel_kind = TexturePixelKind::RGBA8;
+    decompressed.minification_filter = data.minification_filter;
+    decompressed.magnification_filter = data.magnification_filter;
+    decompressed.s_wrap_mode = data.s_wrap_mode;
+    decompressed.t_wrap_mode = data.t_wrap_mode;
+    decompressed.mip_count = data.mip_count;
+    decompressed.anisotropy = data.anisotropy;
+    decompressed.is_render_target = data.is_render_target;
+    decompressed.is_srgb = data.is_srgb;
+    return Ok(decompressed);
+}
+
+/// Appends the RGBA8 pixels of one compressed image to `rgba`.
+fn decode_image(pixel_kind: PixelKind,image: &[u8],width: usize,height: usize,rgba: &mut Vec<u8>)->Result<(),String>{
+    let mut pixels = vec![0u32;width * height];
+    let result = match pixel_kind{
+        PixelKind::DXT1RGB | PixelKind::DXT1RGBA | PixelKind::DXT1SRGB | PixelKind::DXT1SRGBA=>texture2ddecoder::decode_bc1(image,width,height,&mut pixels),
+        PixelKind::DXT5RGBA | PixelKind::DXT5SRGBA=>texture2ddecoder::decode_bc3(image,width,height,&mut pixels),
+        PixelKind::R8RGTC=>texture2ddecoder::decode_bc4(image,width,height,&mut pixels),
+        PixelKind::RG8RGTC=>texture2ddecoder::decode_bc5(image,width,height,&mut pixels),
+        PixelKind::ETC2RGB8 | PixelKind::ETC2SRGB8=>texture2ddecoder::decode_etc2_rgb(image,width,height,&mut pixels),
+        PixelKind::ETC2RGB8A1 | PixelKind::ETC2SRGB8A1=>texture2ddecoder::decode_etc2_rgba1(image,width,height,&mut pixels),
+        PixelKind::ETC2RGBA8 | PixelKind::ETC2SRGBA8=>texture2ddecoder::decode_etc2_rgba8(image,width,height,&mut pixels),
+        PixelKind::EACR11=>texture2ddecoder::decode_eacr(image,width,height,&mut pixels),
+        PixelKind::EACRG11=>texture2ddecoder::decode_eacrg(image,width,height,&mut pixels),
+        PixelKind::ASTC4x4
+        | PixelKind::ASTC4x4SRGB
+        | PixelKind::ASTC5x5
+        | PixelKind::ASTC5x5SRGB
+        | PixelKind::ASTC6x6
+        | PixelKind::ASTC6x6SRGB
+        | PixelKind::ASTC8x8
+        | PixelKind::ASTC8x8SRGB=>{
+            let (block_width,block_height) = astc_footprint(pixel_kind);
+            texture2ddecoder::decode_astc(image,width,height,block_width,block_height,&mut pixels)
+        },
+        _=>return Err(format!("{:?} cannot be decompressed",pixel_kind)),
+    };
+    result.map_err(|e|e.to_string())?;
+
+    // DXT1 without alpha may still decode its transparent color
+    let is_opaque = matches!(pixel_kind,PixelKind::DXT1RGB | PixelKind::DXT1SRGB | PixelKind::ETC2RGB8 | PixelKind::ETC2SRGB8);
+    rgba.reserve(pixels.len() * 4);
+    for pixel in pixels{
+        // the decoder packs BGRA into little endian words
+        let [b,g,r,a] = pixel.to_le_bytes();
+        rgba.extend_from_slice(&[r,g,b,if is_opaque{255}else{a}]);
+    }
+    return Ok(());
+}
@@ -0,0 +1,147 @@
+//! Cooked `.texture` files, written by the asset processor and read by `TextureAssetLoader`.
+//!
+//! Layout, all integers little endian:
+//! ```text
+//! magic             b"PFTX"
+//! version           u32
+//! kind              u8 (0 line, 1 rectangle, 2 cube, 3 volume, 4 rectangle array), width u32,
+//!                   height u32, depth or layer count u32
+//! pixel kind        u32, `TexturePixelKind` id
+//! filters           minification u32, magnification u32
+//! wrap modes        s u32, t u32
+//! mip count         u32
+//! anisotropy        f32
+//! is srgb           u8
+//! bytes             length u64, then every mip level, largest first
+//! ```
+
+use super::texture::{TextureData,TextureKind,TexturePixelKind,TextureMinificationFilter,TextureMagnificationFilter,TextureWrapMode,TextureBytes};
+use crate::core::byteorder::{LittleEndian,ReadBytesExt,WriteBytesExt};
+use std::io::{self,Cursor,Read,Write};
+use std::path::PathBuf;
+
+pub const TEXTURE_MAGIC: &[u8; 4] = b"PFTX";
+pub const TEXTURE_VERSION: u32 = 1;
+
+pub fn write_cooked_texture<W: Write>(data: &TextureData,writer: &mut W)->io::Result<()>{
+    writer.write_all(TEXTURE_MAGIC)?;
+    writer.write_u32::<LittleEndian>(TEXTURE_VERSION)?;
+    let (kind,width,height,depth) = match data.kind{
+        TextureKind::Line{length}=>(0u8,length,1,1),
+        TextureKind::Rectangle{width,height}=>(1,width,height,1),
+        TextureKind::Cube{width,height}=>(2,width,height,1),
+        TextureKind::Volume{width,height,depth}=>(3,width,height,depth),
+        TextureKind::RectangleArray{width,height,layers}=>(4,width,height,layers),
+    };
+    writer.write_u8(kind)?;
+    writer.write_u32::<LittleEndian>(width)?;
+    writer.write_u32::<LittleEndian>(height)?;
+    writer.write_u32::<LittleEndian>(depth)?;
+    writer.write_u32::<LittleEndian>(data.pixel_kind.id())?;
+    writer.write_u32::<LittleEndian>(data.minification_filter as u32)?;
+    writer.write_u32::<LittleEndian>(data.magnification_filter as u32)?;
+    writer.write_u32::<LittleEndian>(data.s_wrap_mode as u32)?;
+    writer.write_u32::<LittleEndian>(data.t_wrap_mode as u32)?;
+    writer.write_u32::<LittleEndian>(data.mip_count)?;
+    writer.write_f32::<LittleEndian>(data.anisotropy)?;
+    writer.write_u8(data.is_srgb as u8)?;
+    writer.write_u64::<LittleEndian>(data.bytes.len() as u64)?;
+    writer.write_all(&data.bytes)?;
+    return Ok(());
+}
+
+pub fn read_cooked_texture(bytes: &[u8],path: PathBuf)->Result<TextureData,String>{
+    let mut cursor = Cursor::new(bytes);
+    return read_texture(&mut cursor,path).map_err(|e|e.to_string());
+}
+
+fn read_texture(cursor: &mut Cursor<&[u8]>,path: PathBuf)->io::Result<TextureData>{
+    let mut magic = [0u8;4];
+    cursor.read_exact(&mut magic)?;
+    if &magic != TEXTURE_MAGIC{
+        return Err(invalid_data("not a cooked texture".to_string()));
+    }
+    let version = cursor.read_u32::<LittleEndian>()?;
+    if version != TEXTURE_VERSION{
+        return Err(invalid_data(format!("unsupported cooked texture version {}",version)));
+    }
+    let kind = cursor.read_u8()?;
+    let width = cursor.read_u32::<LittleEndian>()?;
+    let height = cursor.read_u32::<LittleEndian>()?;
+    let depth = cursor.read_u32::<LittleEndian>()?;
+    let kind = match kind{
+        0=>TextureKind::Line{length:width},
+        1=>TextureKind::Rectangle{width,height},
+        2=>TextureKind::Cube{width,height},
+        3=>TextureKind::Volume{width,height,depth},
+        4=>TextureKind::RectangleArray{width,height,layers:depth},
+        _=>return Err(invalid_data(format!("invalid texture kind {}",kind))),
+    };
+    let pixel_kind = TexturePixelKind::new(cursor.read_u32::<LittleEndian>()?).map_err(invalid_data)?;
+    let minification_filter = minification_filter_from_id(cursor.read_u32::<LittleEndian>()?)?;
+    let magnification_filter = magnification_filter_from_id(cursor.read_u32::<LittleEndian>()?)?;
+    let s_wrap_mode = wrap_mode_from_id(cursor.read_u32::<LittleEndian>()?)?;
+    let t_wrap_mode = wrap_mode_from_id(cursor.read_u32::<LittleEndian>()?)?;
+    let mip_count = cursor.read_u32::<LittleEndian>()?;
+    let anisotropy = cursor.read_f32::<LittleEndian>()?;
+    let is_srgb = cursor.read_u8()? != 0;
+    let len = cursor.read_u64::<LittleEndian>()? as usize;
+    let remaining = bytes_left(cursor);
+    if len > remaining{
+        return Err(invalid_data(format!("texture data is truncated, {} of {} bytes",remaining,len)));
+    }
+    let mut bytes = vec![0u8;len];
+    cursor.read_exact(&mut bytes)?;
+
+    let mut data = TextureData::new(path,Vec::new());
+    data.kind = kind;
+    data.bytes = TextureBytes(bytes);
+    data.pixel_kind = pixel_kind;
+    data.minification_filter = minification_filter;
+    data.magnification_filter = magnification_filter;
+    data.s_wrap_mode = s_wrap_mode;
+    data.t_wrap_mode = t_wrap_mode;
+    data.mip_count = mip_count;
+    data.anisotropy = anisotropy;
+    data.is_srgb = is_srgb;
+    return Ok(data);
+}
+
+fn bytes_left(cursor: &Cursor<&[u8]>)->usize{
+    cursor.get_ref().len().saturating_sub(cursor.position() as usize)
+}
+
+fn invalid_data(message: String)->io::Error{
+    io::Error::new(io::ErrorKind::InvalidData,message)
+}
+
+fn minification_filter_from_id(id: u32)->io::Result<TextureMinificationFilter>{
+    match id{
+        0=>Ok(TextureMinificationFilter::Nearest),
+        1=>Ok(TextureMinificationFilter::NearestMipMapNearest),
+        2=>Ok(TextureMinificationFilter::NearestMipMapLinear),
+        3=>Ok(TextureMinificationFilter::Linear),
+        4=>Ok(TextureMinificationFilter::LinearMipMapNearest),
+        5=>Ok(TextureMinificationFilter::LinearMipMapLinear),
+        _=>Err(invalid_data(format!("invalid minification filter {}",id))),
+    }
+}
+
+fn magnification_filter_from_id(id: u32)->io::Result<TextureMagnificationFilter>{
+    match id{
+        0=>Ok(TextureMagnificationFilter::Nearest),
+        1=>Ok(TextureMagnificationFilter::Linear),
+        _=>Err(invalid_data(format!("invalid magnification filter {}",id))),
+    }
+}
+
+fn wrap_mode_from_id(id: u32)->io::Result<TextureWrapMode>{
+    match id{
+        0=>Ok(TextureWrapMode::Repeat),
+        1=>Ok(TextureWrapMode::ClampToEdge),
+        2=>Ok(TextureWrapMode::ClampToBorder),
+        3=>Ok(TextureWrapMode::MirroredRepeat),
+        4=>Ok(TextureWrapMode::MirrorClampToEdge),
+        _=>Err(invalid_data(format!("invalid wrap mode {}",id))),
+    }
+}
@@ -0,0 +1,37 @@
+use super::texture::{TextureData,TextureKind,TexturePixelKind,TextureBytes};
+use image::{DynamicImage,GenericImageView};
+use std::path::PathBuf;
+
+/// Decodes a PNG, JPEG, TGA or BMP file into a single-mip `TextureKind::Rectangle`, keeping the
+/// channel layout and bit depth of the source where a `TexturePixelKind` matches it.
+pub fn decode_image(bytes: &[u8],path: PathBuf)->Result<TextureData,String>{
+    let image = image::load_from_memory(bytes).map_err(|e|e.to_string())?;
+    let (width,height) = image.dimensions();
+    let (pixel_kind,bytes) = match image{
+        DynamicImage::ImageLuma8(image)=>(TexturePixelKind::R8,image.into_raw()),
+        DynamicImage::ImageLumaA8(image)=>(TexturePixelKind::RG8,image.into_raw()),
+        DynamicImage::ImageRgb8(image)=>(TexturePixelKind::RGB8,image.into_raw()),
+        DynamicImage::ImageRgba8(image)=>(TexturePixelKind::RGBA8,image.into_raw()),
+        DynamicImage::ImageLuma16(image)=>(TexturePixelKind::R16,u16_bytes(&image.into_raw())),
+        DynamicImage::ImageLumaA16(image)=>(TexturePixelKind::RG16,u16_bytes(&image.into_raw())),
+        DynamicImage::ImageRgb16(image)=>(TexturePixelKind::RGB16,u16_bytes(&image.into_raw())),
+        DynamicImage::ImageRgba16(image)=>(TexturePixelKind::RGBA16,u16_bytes(&image.into_raw())),
+        DynamicImage::ImageRgb32F(image)=>(TexturePixelKind::RGB32F,f32_bytes(&image.into_raw())),
+        DynamicImage::ImageRgba32F(image)=>(TexturePixelKind::RGBA32F,f32_bytes(&image.into_raw())),
+        image=>(TexturePixelKind::RGBA8,image.to_rgba8().into_raw()),
+    };
+    let mut data = TextureData::new(path,Vec::new());
+    data.kind = TextureKind::Rectangle{width,height};
+    data.pixel_kind = pixel_kind;
+    data.bytes = TextureBytes(bytes);
+    data.mip_count = 1;
+    return Ok(data);
+}
+
+fn u16_bytes(values: &[u16])->Vec<u8>{
+    values.iter().flat_map(|value|value.to_le_bytes()).collect()
+}
+
+fn f32_bytes(values: &[f32])->Vec<u8>{
+    values.iter().flat_map(|value|value.to_le_bytes()).collect()
+}
@@ -1,9 +1,15 @@
-use std::any::Any;
 use crate::asset_server::loader::AssetLoader;
-use crate::asset_server::asset_path::{AssetPath};
-use super::texture::{TextureData,Texture};
+use crate::asset_server::load_context::{LoadContext};
+use crate::asset_server::error::{AssetLoadError};
+use super::texture::{TextureData,Texture,TextureMinificationFilter,TextureMagnificationFilter,TextureWrapMode};
+use super::texture_format::{read_cooked_texture,TEXTURE_MAGIC};
+use super::texture_import::{decode_image};
+use super::texture_mips::{generate_mips,MipFilter};
+use super::texture_container::{read_ktx2,read_dds,KTX2_MAGIC,DDS_MAGIC};
+use image::ImageFormat;
 use std::path::{PathBuf};
-use crate::fs::{read_file};
+use serde::{Deserialize,Serialize};
+use crate::log::{warn};
 
 pub struct TextureAssetLoader{
 
@@ -15,15 +21,115 @@ impl Default for TextureAssetLoader{
     }
 }
 
+/// Import settings of a texture, missing fields keep their default. A `.meta` file could be
+/// `(minification_filter: LinearMipMapLinear, mip_filter: Some(Kaiser), compression: ETC2)`. The asset
+/// processor bakes the settings of a source image into its cooked `.texture`, images loaded
+/// directly get them applied by `TextureAssetLoader`.
+#[derive(Clone,Debug,Deserialize,Serialize)]
+#[serde(default)]
+pub struct TextureImportSettings{
+    pub minification_filter: TextureMinificationFilter,
+    pub magnification_filter: TextureMagnificationFilter,
+    pub s_wrap_mode: TextureWrapMode,
+    pub t_wrap_mode: TextureWrapMode,
+    pub anisotropy: f32,
+    pub is_srgb: bool,
+    /// Generates a full mip chain with the filter, replacing any mips the file has. Mips are
+    /// only sampled with a mip mapping `minification_filter`.
+    pub mip_filter: Option<MipFilter>,
+    /// Drops the pixel data once the texture is uploaded, see `Texture::set_release_cpu_data`.
+    pub release_cpu_data: bool,
+    /// Block compressed format the texture is meant to be stored in. No encoder exists yet,
+    /// textures keep the pixel kind of their source and a warning is logged.
+    pub compression: TextureCompression,
+}
+
+impl Default for TextureImportSettings{
+    fn default()->Self{
+        Self{
+            minification_filter: Default::default(),
+            magnification_filter: Default::default(),
+            s_wrap_mode: Default::default(),
+            t_wrap_mode: Default::default(),
+            anisotropy: 0.0,
+            is_srgb: false,
+            mip_filter: None,
+            release_cpu_data: false,
+            compression: TextureCompression::None,
+        }
+    }
+}
+
+/// Block compression target of `TextureImportSettings`.
+#[derive(Copy,Clone,Debug,PartialEq,Eq,Deserialize,Serialize)]
+pub enum TextureCompression{
+    /// Pixels are kept as they are.
+    None,
+    /// S3TC DXT1, without alpha.
+    DXT1,
+    /// S3TC DXT5, with interpolated alpha.
+    DXT5,
+    /// ETC2 RGB, with EAC alpha for sources with alpha.
+    ETC2,
+}
+
+impl TextureImportSettings{
+    /// Fails when mips are asked for a pixel kind `generate_mips` does not support.
+    pub fn apply(&self,data: &mut TextureData)->Result<(),String>{
+        data.minification_filter = self.minification_filter;
+        data.magnification_filter = self.magnification_filter;
+        data.s_wrap_mode = self.s_wrap_mode;
+        data.t_wrap_mode = self.t_wrap_mode;
+        data.anisotropy = self.anisotropy;
+        data.is_srgb = self.is_srgb;
+        if let Some(filter) = self.mip_filter{
+            generate_mips(data,filter)?;
+        }
+        if self.compression != TextureCompression::None{
+            warn!("{:?} compression of {:?} is not supported yet, it keeps its pixel kind",self.compression,data.path);
+        }
+        return Ok(());
+    }
+}
+
 impl AssetLoader for TextureAssetLoader{
+    type Asset = Texture;
+    type Settings = TextureImportSettings;
+
     fn extensions(&self)->&[&str]{
-        return &["texture"];
+        return &["texture","ktx2","dds","png","jpg","jpeg","tga","bmp"];
+    }
+    /// TGA files have no magic bytes, they are only recognized by their extension.
+    fn sniff(&self,bytes: &[u8])->bool{
+        return bytes.starts_with(TEXTURE_MAGIC)
+            || bytes.starts_with(KTX2_MAGIC)
+            || bytes.starts_with(DDS_MAGIC)
+            || matches!(image::guess_format(bytes),Ok(ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Bmp));
     }
-    fn load(&self,asset_path: &AssetPath)->Box<dyn Any>{
-        let bytes = read_file(asset_path).map_err(|e|format!("{:?} {:?}",e,asset_path)).unwrap();
-        return Box::new(Texture{
-            data:Some(TextureData::new(PathBuf::from(asset_path.path()),bytes)),
-            gpu_texture:None,
-        });
+    /// Reads cooked `.texture` files, KTX2 and DDS containers and decodes images. Cooked files
+    /// already carry the settings they were cooked with, `settings` only applies to the
+    /// others. Containers keep the sRGB flag of their format.
+    fn load(&self,bytes: &[u8],settings: &TextureImportSettings,load_context: &mut LoadContext)->Result<Texture,AssetLoadError>{
+        let path = PathBuf::from(load_context.path().path());
+        let data = if bytes.starts_with(TEXTURE_MAGIC){
+            read_cooked_texture(bytes,path)
+        }else if bytes.starts_with(KTX2_MAGIC) || bytes.starts_with(DDS_MAGIC){
+            let data = if bytes.starts_with(KTX2_MAGIC){read_ktx2(bytes,path)}else{read_dds(bytes,path)};
+            data.and_then(|mut data|{
+                // mips are generated from the sRGB flag of the format
+                let settings = TextureImportSettings{is_srgb:data.is_srgb,..settings.clone()};
+                settings.apply(&mut data)?;
+                Ok(data)
+            })
+        }else{
+            decode_image(bytes,path).and_then(|mut data|{
+                settings.apply(&mut data)?;
+                Ok(data)
+            })
+        };
+        let data = data.map_err(|message|AssetLoadError::Decode{path:load_context.path().clone(),message})?;
+        let mut texture = Texture::from_texture_data(data);
+        texture.set_release_cpu_data(settings.release_cpu_data);
+        return Ok(texture);
     }
 }
@@ -0,0 +1,366 @@
+use super::texture::{TextureData,TextureKind,TexturePixelKind,TextureBytes,TextureWrapMode};
+use serde::{Deserialize,Serialize};
+use std::f32::consts::PI;
+
+/// Filter a mip level is shrunk from the one above it with.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum MipFilter{
+    /// Averages the pixels of the larger level each pixel covers. Cheap and slightly blurry.
+    Box,
+    /// Kaiser windowed sinc. Keeps more detail, at the cost of a wider kernel and faint ringing
+    /// around hard edges.
+    Kaiser,
+}
+
+/// Half width of the Kaiser kernel, in pixels of the smaller level.
+const KAISER_RADIUS: f32 = 3.0;
+const KAISER_ALPHA: f32 = 4.0;
+
+/// Replaces the mips of `data` with a full chain down to 1x1, generated from its largest
+/// level in the layout `TextureBinding::set_data` expects. Every level is half the size of
+/// the one above rounded down, so sizes need not be powers of two. sRGB color is filtered in
+/// linear space, alpha never is sRGB. Repeating textures wrap around their edges while
+/// filtering, the rest clamp.
+///
+/// Only uncompressed pixel kinds of line, rectangle, cube and array textures are supported.
+pub fn generate_mips(data: &mut TextureData,filter: MipFilter)->Result<(),String>{
+    let format = PixelFormat::of(data.pixel_kind)
+        .ok_or_else(||format!("cannot generate mips of {:?} textures",data.pixel_kind))?;
+    let (width,height,images) = match data.kind{
+        TextureKind::Line{length}=>(length,1,1),
+        TextureKind::Rectangle{width,height}=>(width,height,1),
+        TextureKind::Cube{width,height}=>(width,height,6),
+        TextureKind::RectangleArray{width,height,layers}=>(width,height,layers),
+        TextureKind::Volume{..}=>return Err("cannot generate mips of volume textures".to_string()),
+    };
+    let (width,height,images) = (width.max(1) as usize,height.max(1) as usize,images as usize);
+    let image_size = width * height * format.pixel_size();
+    if data.bytes.len() < images * image_size{
+        return Err(format!("texture data is truncated, {} of {} bytes",data.bytes.len(),images * image_size));
+    }
+    let is_srgb = data.is_srgb && format.is_color();
+    // cube faces meet other faces at their edges, wrapping would bleed the opposite side in
+    let is_cube = matches!(data.kind,TextureKind::Cube{..});
+    let wrap_s = !is_cube && data.s_wrap_mode == TextureWrapMode::Repeat;
+    let wrap_t = !is_cube && data.t_wrap_mode == TextureWrapMode::Repeat;
+    let mip_count = full_mip_count(width,height);
+
+    // chains[image][mip]
+    let mut chains = Vec::with_capacity(images);
+    for image in 0..images{
+        let bytes = &data.bytes[image * image_size..(image + 1) * image_size];
+        let mut level = Image{
+            width,
+            height,
+            pixels: format.decode(bytes,is_srgb),
+        };
+        let mut chain = vec![bytes.to_vec()];
+        for _ in 1..mip_count{
+            level = level.shrink(format.channels,filter,wrap_s,wrap_t);
+            chain.push(format.encode(&level.pixels,is_srgb));
+        }
+        chains.push(chain);
+    }
+
+    let mut bytes = Vec::new();
+    for mip in 0..mip_count{
+        for chain in chains.iter(){
+            bytes.extend_from_slice(&chain[mip]);
+        }
+    }
+    data.bytes = TextureBytes(bytes);
+    data.mip_count = mip_count as u32;
+    return Ok(());
+}
+
+/// Levels from `width` x `height` down to 1x1.
+fn full_mip_count(width: usize,height: usize)->usize{
+    let size = width.max(height).max(1);
+    (usize::BITS - size.leading_zeros()) as usize
+}
+
+/// One face or layer of a level, channels of a pixel next to each other, in linear space.
+struct Image{
+    width: usize,
+    height: usize,
+    pixels: Vec<f32>,
+}
+
+impl Image{
+    /// The next level, filtered horizontally then vertically.
+    fn shrink(&self,channels: usize,filter: MipFilter,wrap_s: bool,wrap_t: bool)->Image{
+        let width = (self.width / 2).max(1);
+        let height = (self.height / 2).max(1);
+
+        let taps = resample_taps(self.width,width,filter,wrap_s);
+        let mut rows = vec![0.0;width * self.height * channels];
+        for y in 0..self.height{
+            for (x,taps) in taps.iter().enumerate(){
+                let target = (y * width + x) * channels;
+                for &(source_x,weight) in taps{
+                    let source = (y * self.width + source_x) * channels;
+                    for c in 0..channels{
+                        rows[target + c] += self.pixels[source + c] * weight;
+                    }
+                }
+            }
+        }
+
+        let taps = resample_taps(self.height,height,filter,wrap_t);
+        let mut pixels = vec![0.0;width * height * channels];
+        for (y,taps) in taps.iter().enumerate(){
+            for &(source_y,weight) in taps{
+                let target = y * width * channels;
+                let source = source_y * width * channels;
+                for i in 0..width * channels{
+                    pixels[target + i] += rows[source + i] * weight;
+                }
+            }
+        }
+        return Image{width,height,pixels};
+    }
+}
+
+/// Source pixels and their normalized weights for every pixel of an axis shrunk from
+/// `source_size` to `target_size`.
+fn resample_taps(source_size: usize,target_size: usize,filter: MipFilter,wrap: bool)->Vec<Vec<(usize,f32)>>{
+    let scale = source_size as f32 / target_size as f32;
+    let radius = match filter{
+        MipFilter::Box=>0.5 * scale,
+        MipFilter::Kaiser=>KAISER_RADIUS * scale,
+    };
+    return (0..target_size).map(|x|{
+        let center = (x as f32 + 0.5) * scale;
+        let first = (center - radius).floor() as isize;
+        let last = (center + radius).ceil() as isize;
+        let mut taps: Vec<(usize,f32)> = (first..last).filter_map(|source_x|{
+            let weight = match filter{
+                // the part of the source pixel the target pixel covers, odd sizes split pixels
+                MipFilter::Box=>{
+                    let left = (source_x as f32).max(center - radius);
+                    let right = (source_x as f32 + 1.0).min(center + radius);
+                    (right - left).max(0.0)
+                },
+                MipFilter::Kaiser=>{
+                    let t = (source_x as f32 + 0.5 - center) / scale;
+                    sinc(t) * kaiser(t / KAISER_RADIUS)
+                },
+            };
+            if weight == 0.0{
+                return None;
+            }
+            Some((source_index(source_x,source_size,wrap),weight))
+        }).collect();
+        let sum: f32 = taps.iter().map(|(_,weight)|weight).sum();
+        for tap in taps.iter_mut(){
+            tap.1 /= sum;
+        }
+        taps
+    }).collect();
+}
+
+fn source_index(x: isize,size: usize,wrap: bool)->usize{
+    if wrap{
+        return x.rem_euclid(size as isize) as usize;
+    }
+    return x.clamp(0,size as isize - 1) as usize;
+}
+
+fn sinc(x: f32)->f32{
+    if x.abs() < 1e-6{
+        return 1.0;
+    }
+    return (PI * x).sin() / (PI * x);
+}
+
+/// Kaiser window over [-1, 1].
+fn kaiser(x: f32)->f32{
+    if x.abs() > 1.0{
+        return 0.0;
+    }
+    return bessel_i0(KAISER_ALPHA * (1.0 - x * x).sqrt()) / bessel_i0(KAISER_ALPHA);
+}
+
+/// Zeroth order modified Bessel function of the first kind, by its power series.
+fn bessel_i0(x: f32)->f32{
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half_square = x * x / 4.0;
+    let mut k = 1.0;
+    while term > sum * 1e-7{
+        term *= half_square / (k * k);
+        sum += term;
+        k += 1.0;
+    }
+    return sum;
+}
+
+#[derive(Copy, Clone)]
+enum ChannelType{
+    U8,
+    U16,
+    F32,
+}
+
+/// Layout of the uncompressed pixel kinds mips can be generated for. Channel order does not
+/// matter to filtering, BGR is treated as RGB.
+#[derive(Copy, Clone)]
+struct PixelFormat{
+    channel_type: ChannelType,
+    channels: usize,
+}
+
+impl PixelFormat{
+    fn of(pixel_kind: TexturePixelKind)->Option<Self>{
+        let (channel_type,channels) = match pixel_kind{
+            TexturePixelKind::R8=>(ChannelType::U8,1),
+            TexturePixelKind::RG8=>(ChannelType::U8,2),
+            TexturePixelKind::RGB8 | TexturePixelKind::BGR8=>(ChannelType::U8,3),
+            TexturePixelKind::RGBA8 | TexturePixelKind::BGRA8=>(ChannelType::U8,4),
+            TexturePixelKind::R16=>(ChannelType::U16,1),
+            TexturePixelKind::RG16=>(ChannelType::U16,2),
+            TexturePixelKind::RGB16=>(ChannelType::U16,3),
+            TexturePixelKind::RGBA16=>(ChannelType::U16,4),
+            TexturePixelKind::RGB32F=>(ChannelType::F32,3),
+            TexturePixelKind::RGBA32F=>(ChannelType::F32,4),
+            _=>return None,
+        };
+        Some(Self{channel_type,channels})
+    }
+
+    fn channel_size(&self)->usize{
+        match self.channel_type{
+            ChannelType::U8=>1,
+            ChannelType::U16=>2,
+            ChannelType::F32=>4,
+        }
+    }
+
+    fn pixel_size(&self)->usize{
+        self.channel_size() * self.channels
+    }
+
+    /// Only 8 bit RGB(A) data is ever sRGB encoded.
+    fn is_color(&self)->bool{
+        matches!(self.channel_type,ChannelType::U8) && self.channels >= 3
+    }
+
+    fn is_srgb_channel(&self,channel: usize,is_srgb: bool)->bool{
+        is_srgb && channel < 3
+    }
+
+    fn decode(&self,bytes: &[u8],is_srgb: bool)->Vec<f32>{
+        let channel_size = self.channel_size();
+        return bytes.chunks_exact(channel_size).enumerate().map(|(i,channel)|{
+            let value = match self.channel_type{
+                ChannelType::U8=>channel[0] as f32 / 255.0,
+                ChannelType::U16=>u16::from_le_bytes([channel[0],channel[1]]) as f32 / 65535.0,
+                ChannelType::F32=>f32::from_le_bytes([channel[0],channel[1],channel[2],channel[3]]),
+            };
+            if self.is_srgb_channel(i % self.channels,is_srgb){
+                return srgb_to_linear(value);
+            }
+            value
+        }).collect();
+    }
+
+    fn encode(&self,pixels: &[f32],is_srgb: bool)->Vec<u8>{
+        let mut bytes = Vec::with_capacity(pixels.len() * self.channel_size());
+        for (i,&value) in pixels.iter().enumerate(){
+            let value = if self.is_srgb_channel(i % self.channels,is_srgb){
+                linear_to_srgb(value)
+            }else{
+                value
+            };
+            match self.channel_type{
+                ChannelType::U8=>bytes.push((value.clamp(0.0,1.0) * 255.0).round() as u8),
+                ChannelType::U16=>bytes.extend_from_slice(&((value.clamp(0.0,1.0) * 65535.0).round() as u16).to_le_bytes()),
+                ChannelType::F32=>bytes.extend_from_slice(&value.to_le_bytes()),
+            }
+        }
+        return bytes;
+    }
+}
+
+fn srgb_to_linear(value: f32)->f32{
+    if value <= 0.04045{
+        return value / 12.92;
+    }
+    return ((value + 0.055) / 1.055).powf(2.4);
+}
+
+fn linear_to_srgb(value: f32)->f32{
+    let value = value.clamp(0.0,1.0);
+    if value <= 0.003_130_8{
+        return value * 12.92;
+    }
+    return 1.055 * value.powf(1.0 / 2.4) - 0.055;
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use std::path::PathBuf;
+
+    fn texture(kind: TextureKind,pixel_kind: TexturePixelKind,bytes: Vec<u8>)->TextureData{
+        let mut data = TextureData::new(PathBuf::from("test.png"),bytes);
+        data.kind = kind;
+        data.pixel_kind = pixel_kind;
+        return data;
+    }
+
+    #[test]
+    fn npot_chain_halves_rounding_down(){
+        let mut data = texture(TextureKind::Rectangle{width:5,height:3},TexturePixelKind::R8,vec![100;15]);
+        generate_mips(&mut data,MipFilter::Box).unwrap();
+        // 5x3, 2x1 and 1x1
+        assert_eq!(data.mip_count,3);
+        assert_eq!(data.bytes.len(),15 + 2 + 1);
+        assert_eq!(&data.bytes[..15],&[100;15][..]);
+        assert_eq!(&data.bytes[15..],&[100;3][..]);
+    }
+
+    #[test]
+    fn cube_mips_are_mip_major_then_faces(){
+        let bytes = (0..6u8).flat_map(|face|[face * 10;4]).collect();
+        let mut data = texture(TextureKind::Cube{width:2,height:2},TexturePixelKind::R8,bytes);
+        generate_mips(&mut data,MipFilter::Box).unwrap();
+        assert_eq!(data.mip_count,2);
+        assert_eq!(data.bytes.len(),6 * 4 + 6);
+        assert_eq!(&data.bytes[20..24],&[50;4][..]);
+        assert_eq!(&data.bytes[24..],&[0,10,20,30,40,50][..]);
+    }
+
+    #[test]
+    fn array_layers_get_their_own_chains(){
+        let bytes = [vec![20;16],vec![200;16]].concat();
+        let mut data = texture(TextureKind::RectangleArray{width:4,height:4,layers:2},TexturePixelKind::R8,bytes);
+        generate_mips(&mut data,MipFilter::Kaiser).unwrap();
+        assert_eq!(data.mip_count,3);
+        assert_eq!(data.bytes.len(),2 * 16 + 2 * 4 + 2);
+        assert_eq!(&data.bytes[32..40],&[20,20,20,20,200,200,200,200][..]);
+        assert_eq!(&data.bytes[40..],&[20,200][..]);
+    }
+
+    #[test]
+    fn srgb_color_is_averaged_in_linear_space(){
+        let black_and_white = vec![0,0,0,0,255,255,255,255];
+        let mut linear = texture(TextureKind::Rectangle{width:2,height:1},TexturePixelKind::RGBA8,black_and_white.clone());
+        generate_mips(&mut linear,MipFilter::Box).unwrap();
+        assert_eq!(&linear.bytes[8..],&[128,128,128,128][..]);
+
+        let mut srgb = texture(TextureKind::Rectangle{width:2,height:1},TexturePixelKind::RGBA8,black_and_white);
+        srgb.is_srgb = true;
+        generate_mips(&mut srgb,MipFilter::Box).unwrap();
+        // half of linear white is 188 in sRGB, alpha stays linear
+        assert_eq!(&srgb.bytes[8..],&[188,188,188,128][..]);
+    }
+
+    #[test]
+    fn compressed_and_volume_textures_are_rejected(){
+        let mut data = texture(TextureKind::Rectangle{width:4,height:4},TexturePixelKind::DXT1RGB,vec![0;8]);
+        assert!(generate_mips(&mut data,MipFilter::Box).is_err());
+        let mut data = texture(TextureKind::Volume{width:2,height:2,depth:2},TexturePixelKind::R8,vec![0;8]);
+        assert!(generate_mips(&mut data,MipFilter::Box).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use super::{TextureMinificationFilter,TextureMagnificationFilter};
+use super::{TextureMinificationFilter,TextureMagnificationFilter,TextureWrapMode};
 
 #[derive(Copy, Clone, PartialOrd, PartialEq, Eq, Hash,Debug)]
 #[repr(u32)]
@@ -44,6 +44,27 @@ pub enum WrapMode {
     MirrorClampToEdge = glow::MIRROR_CLAMP_TO_EDGE,
 }
 
+impl From<TextureWrapMode> for WrapMode {
+    fn from(v: TextureWrapMode) -> Self {
+        match v {
+            TextureWrapMode::Repeat => Self::Repeat,
+            TextureWrapMode::ClampToEdge => Self::ClampToEdge,
+            TextureWrapMode::ClampToBorder => Self::ClampToBorder,
+            TextureWrapMode::MirroredRepeat => Self::MirroredRepeat,
+            TextureWrapMode::MirrorClampToEdge => Self::MirrorClampToEdge,
+        }
+    }
+}
+
+/// Texture coordinate a wrap mode applies to.
+#[derive(Copy, Clone, Eq, PartialEq,Debug)]
+#[repr(u32)]
+pub enum Coordinate {
+    S = glow::TEXTURE_WRAP_S,
+    T = glow::TEXTURE_WRAP_T,
+    R = glow::TEXTURE_WRAP_R,
+}
+
 impl From<TextureMinificationFilter> for MinificationFilter {
     fn from(v: TextureMinificationFilter) -> Self {
         match v {
@@ -0,0 +1,87 @@
+use crate::asset_server::saver::AssetSaver;
+use crate::asset_server::asset_path::{AssetPath};
+use crate::asset_server::error::{AssetSaveError};
+use super::texture::{Texture};
+use super::texture_format::{write_cooked_texture};
+
+/// Writes textures as cooked `.texture` files, which `TextureAssetLoader` reads back.
+#[derive(Default)]
+pub struct TextureAssetSaver{}
+
+impl AssetSaver for TextureAssetSaver{
+    type Asset = Texture;
+
+    fn extensions(&self)->&[&str]{
+        return &["texture"];
+    }
+    /// Only the pixel data is saved, a texture that lives on the GPU alone cannot be.
+    fn save(&self,texture: &Texture,asset_path: &AssetPath)->Result<Vec<u8>,AssetSaveError>{
+        let data = texture.data.as_ref().ok_or_else(||AssetSaveError::Encode{
+            path:asset_path.clone(),
+            message:"texture has no pixel data, it may have been released after upload".to_string(),
+        })?;
+        let mut bytes = Vec::new();
+        write_cooked_texture(data,&mut bytes)
+            .map_err(|e|AssetSaveError::Encode{path:asset_path.clone(),message:e.to_string()})?;
+        return Ok(bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::asset_server::asset_server::{AssetServer,register_asset,add_loader,add_saver};
+    use crate::asset_server::assets::{Assets};
+    use crate::asset_server::io::{MemoryAssetIo};
+    use crate::asset_server::load_state::{LoadState};
+    use crate::asset_server::test_util::{test_app,asset_server,update_until_loaded};
+    use super::super::texture::{TextureData,TextureKind,TexturePixelKind,TextureBytes,TextureMinificationFilter,TextureMagnificationFilter,TextureWrapMode};
+    use super::super::texture_loader::{TextureAssetLoader};
+    use std::path::PathBuf;
+
+    /// A 4x2 RGBA texture with two mips and none of the default settings.
+    fn texture_data()->TextureData{
+        let mut data = TextureData::new(PathBuf::from("source.png"),Vec::new());
+        data.kind = TextureKind::Rectangle{width:4,height:2};
+        data.pixel_kind = TexturePixelKind::RGBA8;
+        data.bytes = TextureBytes((0..40).collect());
+        data.minification_filter = TextureMinificationFilter::NearestMipMapLinear;
+        data.magnification_filter = TextureMagnificationFilter::Nearest;
+        data.s_wrap_mode = TextureWrapMode::ClampToEdge;
+        data.t_wrap_mode = TextureWrapMode::MirroredRepeat;
+        data.mip_count = 2;
+        data.anisotropy = 8.0;
+        data.is_srgb = true;
+        return data;
+    }
+
+    #[test]
+    fn saved_texture_loads_back_unchanged(){
+        let mut app = test_app(MemoryAssetIo::default());
+        register_asset::<Texture>(&mut app);
+        add_loader(&mut app,TextureAssetLoader::default());
+        add_saver(&mut app,TextureAssetSaver::default());
+
+        let handle = app.world.get_resource_mut::<Assets<Texture>>().unwrap()
+            .add(Texture::from_texture_data(texture_data()));
+        let assets = app.world.get_resource::<Assets<Texture>>().unwrap();
+        app.world.get_resource::<AssetServer>().unwrap().save(assets,&handle,"saved/a.texture").unwrap();
+
+        let loaded = asset_server(&mut app).load::<Texture,_>("saved/a.texture");
+        assert_eq!(update_until_loaded(&mut app,loaded.id()),LoadState::Loaded);
+        let assets = app.world.get_resource::<Assets<Texture>>().unwrap();
+        let loaded = assets.get_asset(&loaded).unwrap().data().unwrap();
+        let saved = texture_data();
+        assert!(matches!(loaded.kind,TextureKind::Rectangle{width:4,height:2}));
+        assert_eq!(loaded.pixel_kind,saved.pixel_kind);
+        assert_eq!(loaded.bytes.0,saved.bytes.0);
+        assert_eq!(loaded.minification_filter,saved.minification_filter);
+        assert_eq!(loaded.magnification_filter,saved.magnification_filter);
+        assert_eq!(loaded.s_wrap_mode,saved.s_wrap_mode);
+        assert_eq!(loaded.t_wrap_mode,saved.t_wrap_mode);
+        assert_eq!(loaded.mip_count,saved.mip_count);
+        assert_eq!(loaded.anisotropy,saved.anisotropy);
+        assert_eq!(loaded.is_srgb,saved.is_srgb);
+        assert_eq!(loaded.path,PathBuf::from("saved/a.texture"));
+    }
+}
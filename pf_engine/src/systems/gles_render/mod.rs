@@ -10,11 +10,11 @@ use bevy::{
 use glow::HasContext;
 
 use crate::{
-    log::{info},
+    log::{info,error},
     events::define::{SystemEvents},
     render::gpu_program::GPUProgram,
     render::mesh::Mesh,
-    render::{Material,Texture},
+    render::{Material,Texture,Camera,RenderTarget},
     asset_server::{Assets},
 };
 
@@ -83,7 +83,9 @@ fn render_frame(
      mut system_events: EventReader<SystemEvents>,
      mut query: Query<&mut Mesh,Without<Material>>,
      material_mesh_query: Query<(&mut Mesh,&mut Material)>,
+     camera_query: Query<&Camera>,
      mut texture_assets: ResMut<Assets<Texture>>,
+     mut render_targets: ResMut<Assets<RenderTarget>>,
      mut renderer: ResMut<Renderer>,
     ) {
     for ev in system_events.iter() {
@@ -92,6 +94,7 @@ fn render_frame(
                 let surface = renderer.egl.entry_create_surface(*window_ptr).unwrap();
                 renderer.egl.attach_surface_to_ctx(surface).unwrap();
                 info!("✅ attached new surface to elgl ctx ");
+                renderer.capture_window_viewport();
                 if renderer.gpu_program.is_none(){
                     renderer.gpu_program = Some(GPUProgram::standard(&mut renderer.state))
                 }
@@ -107,23 +110,52 @@ fn render_frame(
         return;
     }
 
+    // { delete GL objects of assets dropped since the last frame
+    renderer.state.delete_dropped_objects();
+    // }
+
     // { bind program
     renderer.bind_gpu_program();
     // }
 
-    unsafe {
-        renderer.state.gl.clear_color(0.1,0.2,0.3,0.5);
-        renderer.state.gl.clear(glow::COLOR_BUFFER_BIT);
+    // { draw every camera, in order. Without cameras draw the window as a default one would
+    let mut cameras: Vec<&Camera> = camera_query.iter().collect();
+    cameras.sort_by_key(|camera|camera.order);
+    let window_camera = Camera::default();
+    if cameras.is_empty(){
+        cameras.push(&window_camera);
     }
-    
-    // { draw meshes
-    for mut mesh in query.iter_mut() {
-        mesh.draw(&mut renderer.state);
+    for camera in cameras {
+        let target_texture = match renderer.bind_camera_target(camera,render_targets.as_mut(),texture_assets.as_mut()){
+            Ok(target_texture)=>target_texture,
+            Err(e)=>{
+                error!("failed to bind the render target of a camera: {}",e);
+                continue;
+            },
+        };
+
+        unsafe {
+            let [red,green,blue,alpha] = camera.clear_color;
+            renderer.state.gl.clear_color(red,green,blue,alpha);
+            renderer.state.gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT | glow::STENCIL_BUFFER_BIT);
+        }
+
+        // { draw meshes
+        for mut mesh in query.iter_mut() {
+            mesh.draw(&mut renderer.state);
+        }
+        // }
+
+        // { draw material mesh
+        renderer.draw_material_mesh(&material_mesh_query,texture_assets.as_mut(),target_texture.as_ref());
+        // }
     }
     // }
 
-    // { draw material mesh
-    renderer.draw_material_mesh(material_mesh_query,texture_assets.as_mut());
+    // { back to the window for whatever draws after
+    renderer.state.set_framebuffer(None);
+    let window_viewport = renderer.window_viewport;
+    renderer.state.set_viewport(window_viewport);
     // }
 
     renderer.egl.swap_buffers();
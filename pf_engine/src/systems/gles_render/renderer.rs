@@ -4,7 +4,9 @@ use crate::render::gpu_program::GpuProgramBinding;
 use glow::HasContext;
 use crate::render::mesh::Mesh;
 use crate::render::{Material};
-use crate::render::{Texture,GPUTexture,GpuTextureKind,PixelKind,MinificationFilter,MagnificationFilter};
+use crate::render::{Texture,Camera,RenderTarget,FrameworkError};
+use crate::asset_server::handle::{Handle};
+use crate::core::math::Rect;
 use crate::asset_server::{Assets};
 use bevy::ecs::system::Query;
 use crate::log::{info};
@@ -14,6 +16,8 @@ pub struct Renderer{
     pub(in crate) state: PipelineState, 
     pub(in crate) egl: pf_egl::Egl14,
     pub(in crate) gpu_program: Option<GPUProgram>,
+    /// Viewport of the window surface, restored after drawing into render targets.
+    pub(in crate) window_viewport: Rect<i32>,
 }
 
 impl Renderer {
@@ -24,9 +28,41 @@ impl Renderer {
             state: state,
             egl: egl,
             gpu_program: None,
+            window_viewport: Rect::new(0, 0, 1, 1),
         }
     }
 
+    /// Reads the viewport GL gave the surface, call it once the surface is attached.
+    pub(in crate) fn capture_window_viewport(&mut self){
+        let mut viewport = [0i32;4];
+        unsafe {
+            self.state.gl.get_parameter_i32_slice(glow::VIEWPORT,&mut viewport);
+        }
+        self.window_viewport = Rect::new(viewport[0],viewport[1],viewport[2],viewport[3]);
+    }
+
+    /// Binds the window or the frame buffer of the camera's render target, returns the color
+    /// texture of the target.
+    pub(in crate) fn bind_camera_target(
+        &mut self,
+        camera: &Camera,
+        render_targets: &mut Assets<RenderTarget>,
+        texture_assets: &mut Assets<Texture>,
+        )->Result<Option<Handle<Texture>>,FrameworkError>{
+        let target_handle = match &camera.target {
+            Some(target_handle)=>target_handle,
+            None=>{
+                self.state.set_framebuffer(None);
+                self.state.set_viewport(self.window_viewport);
+                return Ok(None);
+            },
+        };
+        let target = render_targets.get_asset_mut(target_handle)
+            .ok_or_else(||FrameworkError::Custom("render target of a camera is not loaded".to_string()))?;
+        target.frame_buffer(&mut self.state,texture_assets)?.bind(&mut self.state);
+        return Ok(Some(target.color_texture().clone_weak()));
+    }
+
     pub(in crate) fn bind_gpu_program(&mut self)->Option<GpuProgramBinding>{
         self.gpu_program.as_ref().and_then(|gpu_program|{
             Some(gpu_program.bind(&mut self.state))
@@ -34,8 +70,9 @@ impl Renderer {
     }
 
     pub(in crate) fn draw_material_mesh(
-        &mut self,material_mesh_query: Query<(&mut Mesh,&mut Material)>,
+        &mut self,material_mesh_query: &Query<(&mut Mesh,&mut Material)>,
         texture_assets: &mut Assets<Texture>,
+        target_texture: Option<&Handle<Texture>>,
         ){
         for (mesh,material) in material_mesh_query.iter() {
             let texture_handle = match &material.texture {
@@ -44,29 +81,18 @@ impl Renderer {
             };
             info!("texture_handle {:?}",texture_handle);
 
+            // GL cannot sample the texture being drawn into
+            if target_texture.map_or(false,|target_texture|target_texture.id() == texture_handle.id()){
+                continue;
+            }
+
             let texture =match texture_assets.get_asset_mut(&texture_handle){
                 Some(texture)=>texture,
                 None=>continue,
             };
             info!("texture {:?}",texture);
 
-            let gpu_texture = texture.gpu_texture.get_or_insert_with(
-                || {
-                    let  texture_data= texture.data.as_ref().unwrap();
-                    info!("texture_data {:?}",texture_data);
-                    //GPUTexture::new(
-                    //     &mut self.state,
-                    //     texture_data.kind.into(),
-                    //     texture_data.pixel_kind.into(),
-                    //     texture_data.minification_filter.into(),
-                    //     texture_data.magnification_filter.into(),
-                    //     texture_data.mip_count.try_into().unwrap(),
-                    //     Some(texture_data.bytes.as_ref()),
-                    //    ).unwrap()
-                    // GPUTexture::red_dummy(&mut self.state)
-                     GPUTexture::white_dummy(&mut self.state)
-                }
-                );
+            let gpu_texture = texture.gpu_texture(&mut self.state);
             info!("gpu_texture {:?}",gpu_texture);
 
             // { set texture
@@ -10,6 +10,8 @@ use crate::core::{
 };
 
 use  glam::f32::{Mat4,Vec3,Vec4};
+use bevy::reflect::TypeUuid;
+use crate::asset_server::asset_memory::{AssetMemory,AssetMemoryUsage};
 
 use crate::core::math::TriangleDefinition;
 
@@ -18,7 +20,8 @@ use super::vertex::{StaticVertex};
 /// Data source of a surface. Each surface can share same data source, this is used
 /// in instancing technique to render multiple instances of same model at different
 /// places.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, TypeUuid)]
+#[uuid = "a3f1c9e2-5b7d-4e0a-9c61-2d8b4f7e1a53"]
 pub struct SurfaceData {
     /// Current vertex buffer.
     pub vertex_buffer: VertexBuffer,
@@ -29,6 +32,16 @@ pub struct SurfaceData {
     //is_procedural: bool,
 }
 
+impl AssetMemoryUsage for SurfaceData {
+    fn memory_usage(&self) -> AssetMemory {
+        AssetMemory {
+            cpu_bytes: self.vertex_buffer.raw_data().len()
+                + self.geometry_buffer.len() * std::mem::size_of::<TriangleDefinition>(),
+            gpu_bytes: 0,
+        }
+    }
+}
+
 impl SurfaceData {
     /// Creates new data source using given vertices and indices.
     pub fn new(
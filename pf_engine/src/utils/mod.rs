@@ -20,3 +20,11 @@ pub fn array_as_u8_slice<T: Sized>(v: &[T]) -> &'_ [u8] {
         std::slice::from_raw_parts(v.as_ptr() as *const u8, std::mem::size_of::<T>() * v.len())
     }
 }
+
+/// 64 bit FNV-1a hash of a byte slice. Unlike the std and ahash hashers its result only
+/// depends on the input, so it can be persisted and compared across machines.
+pub fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| (hash ^ byte as u64).wrapping_mul(PRIME))
+}